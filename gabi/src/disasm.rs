@@ -0,0 +1,47 @@
+use gabriele::cmd::{Cmd, CmdJump};
+use gabriele::symbol::Symbol;
+use std::io::{self, Write};
+
+/// Finds the plain character moulded on the petal of the daisy wheel.
+pub fn petal_character(wheel: &[Symbol], petal: u8) -> Option<char> {
+    wheel
+        .iter()
+        .find(|symbol| matches!(&symbol.signs, [Some(sign), None] if sign.idx == petal))
+        .map(|symbol| symbol.character)
+}
+
+/// Describes a single 2-byte word found at the `offset` in one line.
+pub fn describe(offset: usize, word: u16, wheel: &[Symbol]) -> String {
+    let details = match Cmd::from_u16(word) {
+        Ok(Cmd::Motion(motion)) => format!("motion  {} {}", motion.dir, motion.value),
+        Ok(Cmd::Jump(CmdJump::Plus)) => String::from("jump    +x"),
+        Ok(Cmd::Jump(CmdJump::Minus)) => String::from("jump    -x"),
+        Ok(Cmd::Jump(session)) => format!("session {session}"),
+        Ok(cmd @ (Cmd::SymbolLow(sym) | Cmd::SymbolHigh(sym))) => {
+            let petal = cmd.petal().unwrap_or(sym.code);
+            let character = petal_character(wheel, petal)
+                .map(|chr| format!("{chr:?}"))
+                .unwrap_or_else(|| String::from("?"));
+            format!(
+                "symbol  {petal:3} {character:5} {} {}",
+                sym.attr.impression, sym.attr.direction
+            )
+        }
+        Err(e) => format!("{e}"),
+    };
+    format!("{offset:06x}  {word:04x}  {details}")
+}
+
+/// Prints the instruction file written by `sim` in a human-readable form,
+/// one command per line.
+pub fn disassemble(data: &[u8], wheel: &[Symbol], out: &mut impl Write) -> io::Result<()> {
+    let mut words = data.chunks_exact(2);
+    for (idx, chunk) in words.by_ref().enumerate() {
+        let word = u16::from_be_bytes([chunk[0], chunk[1]]);
+        writeln!(out, "{}", describe(idx * 2, word, wheel))?;
+    }
+    if let [byte] = words.remainder() {
+        writeln!(out, "{:06x}  {byte:02x}    incomplete word", data.len() - 1)?;
+    }
+    Ok(())
+}
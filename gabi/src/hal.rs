@@ -1,104 +1,76 @@
-use anyhow::{bail, Context};
-use bytes::Bytes;
+use anyhow::anyhow;
 use gabriele::printing::Instruction;
-use log::debug;
-use std::net::SocketAddr;
-use std::sync::Arc;
+use log::{debug, error, warn};
 use std::time::Duration;
-use tcp_client::run_tcp_client;
-use tokio::sync::broadcast::{self, Sender};
+use tcp_client::{Status, Transport};
 use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::sync::Notify;
-use tokio_util::sync::CancellationToken;
+use tokio::sync::watch;
 
-pub struct Hal {
+/// Sends the Instructions received from the Machine to the typewriter through the `Transport`.
+pub struct Hal<T: Transport> {
     receiver: UnboundedReceiver<Instruction>,
-    notifier: Arc<Notify>,
-    tx: Option<Sender<Bytes>>,
-    c_token: CancellationToken,
-    socket_addr: SocketAddr,
+    transport: T,
+    handled: watch::Sender<usize>,
 }
 
-impl Hal {
-    pub fn new(receiver: UnboundedReceiver<Instruction>, socket_addr: SocketAddr) -> Self {
-        let notifier = Arc::new(Notify::new());
-
-        let c_token = CancellationToken::new();
+impl<T: Transport> Hal<T> {
+    pub fn new(receiver: UnboundedReceiver<Instruction>, transport: T) -> Self {
         Hal {
             receiver,
-            tx: None,
-            notifier,
-            c_token,
-            socket_addr,
+            transport,
+            handled: watch::Sender::new(0),
         }
     }
 
-    pub async fn run(&mut self) -> anyhow::Result<()> {
-        let (tx, rx) = broadcast::channel::<Bytes>(1024);
-        self.tx.replace(tx);
+    /// The number of Instructions handled so far, see `SenderWrapper::with_progress`.
+    pub fn progress(&self) -> watch::Receiver<usize> {
+        self.handled.subscribe()
+    }
 
-        let token = self.c_token.clone();
-        let handle = run_tcp_client(self.socket_addr, rx, self.notifier.clone(), token);
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
 
-        self.notifier.notified().await;
-        // self.prepare()?;
-        debug!("runner is started successfully");
-        self.elaborate_messages().await?;
+    /// Sends the Instructions until the Machine halts, stops earlier on Ctrl-C.
+    pub async fn run(&mut self) -> anyhow::Result<()> {
+        let result = tokio::select! {
+            result = self.serve() => result,
+            _ = tokio::signal::ctrl_c() => {
+                warn!("Ctrl-C is captured, the printing is stopped");
+                Err(anyhow!("the printing is interrupted"))
+            }
+        };
+        if self.transport.status() == Status::Stalled {
+            error!("The typewriter does not respond, the printing is stopped");
+        }
         debug!("sender channel is disconnected");
-        self.c_token.cancel();
-        // self.shutdown()?;
-        tokio::time::sleep(Duration::from_secs(1)).await;
+        self.transport.disconnect().await?;
+        result
+    }
 
-        let _ = tokio::join!(handle);
-        Ok(())
+    async fn serve(&mut self) -> anyhow::Result<()> {
+        self.transport.connect().await?;
+        debug!("runner is started successfully");
+        self.elaborate_messages().await
     }
 
     pub async fn elaborate_messages(&mut self) -> anyhow::Result<()> {
         while let Some(item) = self.receiver.recv().await {
             debug!("received message: {:?}", &item);
             match item {
-                Instruction::SendBytes(details) => self.transmit_bytes(details)?,
+                Instruction::SendBytes(details) => self.transmit_bytes(details).await?,
+                Instruction::Idle(ms) => tokio::time::sleep(Duration::from_millis(ms.into())).await,
                 Instruction::Halt => break,
             }
+            self.handled.send_modify(|count| *count += 1);
         }
-        // drop Sender
-        let _ = self.tx.take();
         Ok(())
     }
 
-    pub fn transmit_bytes(&self, word: u16) -> anyhow::Result<()> {
-        if let Some(ref tx) = self.tx {
-            tx.send(Bytes::copy_from_slice(&word.to_be_bytes()))
-                .map(|_| ())
-                .context("cannot transmit bytes")
-        } else {
-            bail!("cannot transmit bytes, channel is disconnected")
+    pub async fn transmit_bytes(&mut self, word: u16) -> anyhow::Result<()> {
+        for byte in word.to_be_bytes() {
+            self.transport.send(byte).await?;
         }
+        Ok(())
     }
-
-    // pub fn prepare(&self) -> anyhow::Result<()> {
-    //     self.go_online()?;
-    //     self.start_accepting_commands()
-    // }
-
-    // fn go_offline(&self) -> anyhow::Result<()> {
-    //     self.transmit_bytes(&[0xA0, 0x00])
-    // }
-
-    // fn go_online(&self) -> anyhow::Result<()> {
-    //     self.transmit_bytes(&[0xA1, 0x00])
-    // }
-    //
-    // fn start_accepting_commands(&self) -> anyhow::Result<()> {
-    //     self.transmit_bytes(&[0xA2, 0x00])
-    // }
-    //
-    // fn stop_accepting_commands(&self) -> anyhow::Result<()> {
-    //     self.transmit_bytes(&[0xA3, 0x00])
-    // }
-    //
-    // pub fn shutdown(&self) -> anyhow::Result<()> {
-    //     self.stop_accepting_commands()?;
-    //     self.go_offline()
-    // }
 }
@@ -1,14 +1,102 @@
+pub mod disasm;
 mod hal;
+pub mod markup;
+pub mod paper;
+mod serial;
+mod transport;
 
+use clap::ValueEnum;
 use gabriele::machine::InstructionSender;
 use gabriele::printing::Instruction;
+use gabriele::symbol::Symbol;
 pub use hal::Hal;
+use log::{debug, error};
+pub use serial::{Confirmation, SerialTransport};
+use std::sync::atomic::{AtomicUsize, Ordering};
+pub use tcp_client::{Stalled, Status, TcpTransport, Transport, DEFAULT_ECHO_TIMEOUT};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
+pub use transport::{FileDump, Loopback};
 
-pub struct SenderWrapper(pub UnboundedSender<Instruction>);
+/// Passes the Instructions from the Machine to the `Hal`.
+pub struct SenderWrapper {
+    tx: UnboundedSender<Instruction>,
+    sent: AtomicUsize,
+    handled: Option<watch::Receiver<usize>>,
+}
+
+impl SenderWrapper {
+    pub fn new(tx: UnboundedSender<Instruction>) -> Self {
+        SenderWrapper {
+            tx,
+            sent: AtomicUsize::new(0),
+            handled: None,
+        }
+    }
+
+    /// Follows the number of Instructions handled by the Hal, see `Hal::progress`,
+    /// the paper change waits for all the Instructions sent before it.
+    pub fn with_progress(mut self, handled: watch::Receiver<usize>) -> Self {
+        self.handled = Some(handled);
+        self
+    }
+
+    /// Returns once the Hal has handled all the Instructions sent so far,
+    /// or right away without the progress of the Hal.
+    pub async fn wait_until_handled(&self) {
+        let Some(handled) = &self.handled else {
+            return;
+        };
+        let sent = self.sent.load(Ordering::Relaxed);
+        if handled
+            .clone()
+            .wait_for(|count| *count >= sent)
+            .await
+            .is_err()
+        {
+            error!("the runner has stopped before the Instructions were handled");
+        }
+    }
+}
 
 impl InstructionSender for SenderWrapper {
     async fn send(&self, instr: Instruction) {
-        self.0.send(instr).expect("cannot send instruction");
+        self.tx.send(instr).expect("cannot send instruction");
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Waits until the typewriter has ejected the sheet, then prompts the operator on stderr,
+    /// stdout carries the logs.
+    /// The confirmation is read on the blocking thread pool through the std stdin,
+    /// its buffer is shared with the text read from stdin.
+    async fn change_paper(&self) {
+        self.wait_until_handled().await;
+        eprintln!("The page is full, insert a new sheet and press return");
+        let confirmation = tokio::task::spawn_blocking(|| {
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)
+        });
+        match confirmation.await {
+            Ok(Ok(_)) => debug!("the new sheet is confirmed"),
+            Ok(Err(e)) => error!("cannot read the confirmation of the new sheet: {e}"),
+            Err(e) => error!("the confirmation of the new sheet is lost: {e}"),
+        }
+    }
+}
+
+/// Daisy wheels known to the application
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Wheel {
+    Standard,
+    /// Proportional spacing with the standard layout of the petals
+    Proportional,
+}
+
+impl Wheel {
+    pub fn symbols(&self) -> &'static [Symbol] {
+        match self {
+            Wheel::Standard => &gabriele::wheels::standard::SYMBOLS,
+            Wheel::Proportional => &gabriele::wheels::proportional::SYMBOLS,
+        }
     }
 }
@@ -1,51 +1,397 @@
-mod hal;
-pub use hal::Hal;
-
 use env_logger::{Builder, Target};
-use gabriele::machine::Machine;
+use gabriele::attributes::Span;
+use gabriele::database::UnknownCharacterPolicy;
+use gabriele::layout::Alignment;
+use gabriele::machine::{Machine, DEFAULT_CARRIAGE_TRAVEL};
+use gabriele::page::Page;
+use gabriele::pause::Pauses;
 use gabriele::printing::Instruction;
-use log::{debug, info};
+use gabriele::resolution::{LineSpacing, Pitch, Resolution, DEFAULT_Y_RESOLUTION};
+use log::{debug, error, info};
 use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
 use std::{fs, io};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 
-use clap::Parser;
-use gabi::SenderWrapper;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use gabi::disasm::disassemble;
+use gabi::markup::{parse, plain_text, spans, Run};
+use gabi::{
+    Confirmation, FileDump, Hal, SenderWrapper, SerialTransport, TcpTransport, Transport, Wheel,
+    DEFAULT_ECHO_TIMEOUT,
+};
 use gabriele::symbol::Symbol;
 
 /// Gabriele
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// IP address of the RP2040 controller, example: 192.168.0.11
+    #[arg(long, required_unless_present_any = ["serial", "dump"], conflicts_with_all = ["serial", "dump"])]
+    ip: Option<Ipv4Addr>,
+
+    /// Serial device wired straight to the typewriter, example: /dev/ttyUSB0
     #[arg(long)]
-    ip: Ipv4Addr,
+    serial: Option<String>,
+
+    /// How the typewriter confirms every byte sent through the serial device
+    #[arg(long, value_enum, default_value_t = Confirmation::Cts)]
+    confirmation: Confirmation,
+
+    /// Milliseconds to wait for the bridge or the serial device to confirm every byte
+    #[arg(long, default_value_t = DEFAULT_ECHO_TIMEOUT.as_millis() as u64)]
+    echo_timeout: u64,
+
+    /// How many times more to wait for a missing confirmation before giving up
+    #[arg(long, default_value_t = 0)]
+    retries: u32,
+
+    /// How many times to try connecting to the RP2040 controller, 5 seconds apart,
+    /// the attempts go on until it is reached when not given
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    connect_attempts: Option<u32>,
+
+    /// Write the commands into a file instead of the typewriter, see `gabi disasm`
+    #[arg(long, conflicts_with = "serial")]
+    dump: Option<String>,
 
     /// Optional path to a text file to be printed
     #[arg(long)]
     text: Option<String>,
+
+    /// The daisy wheel mounted in the typewriter
+    #[arg(long, value_enum, default_value_t = Wheel::Standard)]
+    wheel: Wheel,
+
+    /// Read the text as markup: **bold**, _underline_, `# ` centered headings,
+    /// `-> ` right aligned lines, `::: center` blocks and `\` escapes
+    #[arg(long)]
+    markup: bool,
+
+    /// Print every other line from the right to the left
+    #[arg(long)]
+    bidirectional: bool,
+
+    /// What to do with the characters missing on the daisy wheel
+    #[arg(long, value_enum, default_value_t = Unknown::Abort)]
+    unknown: Unknown,
+
+    /// Replacement for the missing characters when `--unknown substitute` is given
+    #[arg(long, default_value_t = '?')]
+    replacement: char,
+
+    /// Do not check the text file before connecting to the typewriter
+    #[arg(long)]
+    skip_validation: bool,
+
+    /// Switch the typewriter online before printing and offline afterwards,
+    /// for the firmware which does not activate it on its own,
+    /// it is always done through the serial device
+    #[arg(long)]
+    activate: bool,
+
+    /// Column where every line begins
+    #[arg(long, default_value_t = 0)]
+    left_margin: i32,
+
+    /// Column which no line goes beyond, the lines are wrapped at whitespace
+    #[arg(long)]
+    right_margin: Option<i32>,
+
+    /// Do not wrap the lines longer than the space between the margins
+    #[arg(long)]
+    no_wrap: bool,
+
+    /// Placement of the rows between the margins
+    #[arg(long, value_enum, default_value_t = Align::Left)]
+    align: Align,
+
+    /// Number of characters per inch
+    #[arg(long, value_enum, default_value_t = CharacterPitch::Pica)]
+    pitch: CharacterPitch,
+
+    /// Distance between the lines
+    #[arg(long, value_enum, default_value_t = Spacing::Single)]
+    line_spacing: Spacing,
+
+    /// Distance between the tab stops in columns
+    #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(i32).range(1..))]
+    tab_stops: i32,
+
+    /// Number of single-spaced lines (6 per inch) on a paper sheet whatever the line spacing,
+    /// the pages are broken when it is given
+    #[arg(long)]
+    page_length: Option<i32>,
+
+    /// Number of blank single-spaced lines at the top of every page
+    #[arg(long, default_value_t = 6)]
+    top_margin: i32,
+
+    /// Number of blank single-spaced lines at the bottom of every page
+    #[arg(long, default_value_t = 6)]
+    bottom_margin: i32,
+
+    /// Milliseconds to wait after a carriage or paper motion per 100 units of its distance
+    #[arg(long, default_value_t = 0)]
+    motion_pause: u32,
+
+    /// Milliseconds to wait after a new sheet is inserted
+    #[arg(long, default_value_t = 0)]
+    page_pause: u32,
+
+    /// Milliseconds to wait after every command sent to the typewriter
+    #[arg(long, default_value_t = 0)]
+    pacing: u32,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the commands of a binary instruction file, e.g. written by `sim`
+    Disasm {
+        /// Path to the instruction file
+        path: String,
+
+        /// The daisy wheel to look up the printed characters
+        #[arg(long, value_enum, default_value_t = Wheel::Standard)]
+        wheel: Wheel,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Unknown {
+    /// Do not print the input containing missing characters
+    Abort,
+    /// Leave the missing characters out
+    Skip,
+    /// Replace the missing characters with `--replacement`
+    Substitute,
+    /// Replace the missing characters with similar looking ones
+    Transliterate,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Align {
+    Left,
+    Center,
+    Right,
+    /// Stretch the wrapped rows to the right margin
+    Justify,
+}
+
+impl From<Align> for Alignment {
+    fn from(align: Align) -> Self {
+        match align {
+            Align::Left => Alignment::Left,
+            Align::Center => Alignment::Center,
+            Align::Right => Alignment::Right,
+            Align::Justify => Alignment::Justify,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CharacterPitch {
+    /// 10 characters per inch
+    Pica,
+    /// 12 characters per inch
+    Elite,
+    /// 15 characters per inch
+    Micro,
+}
+
+impl From<CharacterPitch> for Pitch {
+    fn from(pitch: CharacterPitch) -> Self {
+        match pitch {
+            CharacterPitch::Pica => Pitch::Pica,
+            CharacterPitch::Elite => Pitch::Elite,
+            CharacterPitch::Micro => Pitch::Micro,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Spacing {
+    /// 6 lines per inch
+    Single,
+    /// 4 lines per inch
+    OneAndHalf,
+    /// 3 lines per inch
+    Double,
+}
+
+impl From<Spacing> for LineSpacing {
+    fn from(spacing: Spacing) -> Self {
+        match spacing {
+            Spacing::Single => LineSpacing::Single,
+            Spacing::OneAndHalf => LineSpacing::OneAndHalf,
+            Spacing::Double => LineSpacing::Double,
+        }
+    }
+}
+
+impl Args {
+    fn resolution(&self) -> Resolution {
+        Resolution::new(self.pitch.into(), self.line_spacing.into())
+    }
+
+    /// Checks the arguments which depend on each other.
+    fn check(&self) -> Result<(), clap::Error> {
+        match self.page_length {
+            Some(length) if self.top_margin + self.bottom_margin >= length => Err(Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "the top and bottom margins of {} and {} lines leave no room \
+                         on the page of {length} lines",
+                        self.top_margin, self.bottom_margin
+                    ),
+                )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The sheet is measured in single-spaced lines, its length does not change
+    /// with `--line-spacing`.
+    fn page(&self) -> Option<Page> {
+        self.page_length.map(|lines| Page {
+            length: lines * DEFAULT_Y_RESOLUTION,
+            top_margin: self.top_margin * DEFAULT_Y_RESOLUTION,
+            bottom_margin: self.bottom_margin * DEFAULT_Y_RESOLUTION,
+        })
+    }
+
+    fn pauses(&self) -> Pauses {
+        Pauses {
+            motion: self.motion_pause,
+            page: self.page_pause,
+            pacing: self.pacing,
+        }
+    }
+
+    fn unknown_character_policy(&self) -> UnknownCharacterPolicy {
+        match self.unknown {
+            Unknown::Abort => UnknownCharacterPolicy::Abort,
+            Unknown::Skip => UnknownCharacterPolicy::Skip,
+            Unknown::Substitute => UnknownCharacterPolicy::Substitute(self.replacement),
+            Unknown::Transliterate => UnknownCharacterPolicy::Transliterate,
+        }
+    }
+}
+
+async fn print(
+    machine: &mut Machine<SenderWrapper, &'static [Symbol]>,
+    input: &str,
+    spans: &[Span],
+) {
+    if let Err(e) = machine.print_spans(input, spans).await {
+        error!("Nothing is printed: {e}");
+        for unknown in machine.unknown_characters(input) {
+            error!("{unknown}");
+        }
+    }
+}
+
+/// Prints the runs as a single text, each with its own attributes and alignment.
+async fn print_runs(machine: &mut Machine<SenderWrapper, &'static [Symbol]>, runs: &[Run]) {
+    print(machine, &plain_text(runs), &spans(runs)).await;
 }
 
-async fn standard_in(machine: &mut Machine<SenderWrapper, &'static [Symbol]>) {
+async fn standard_in(machine: &mut Machine<SenderWrapper, &'static [Symbol]>, markup: bool) {
     debug!("Printing stdin");
-    let stdin = io::stdin();
-    for line in stdin.lines() {
-        if let Ok(mut input) = line {
-            if input != *"exit" {
-                input.push('\n');
-                machine.print(&input).await;
-            } else {
-                break;
-            }
-        } else {
+    // stdin is not locked between the lines, the operator confirms the paper change there
+    loop {
+        let mut input = String::new();
+        if !matches!(io::stdin().read_line(&mut input), Ok(1..)) {
+            break;
+        }
+        let line = input.trim_end_matches(['\r', '\n']);
+        if line == "exit" {
             break;
         }
+        let input = format!("{line}\n");
+        if markup {
+            print_runs(machine, &parse(&input)).await;
+        } else {
+            print(machine, &input, &[]).await;
+        }
+    }
+}
+
+/// Makes a dry run over the input, returns `false` when it should not be printed.
+async fn validate(machine: &Machine<SenderWrapper, &'static [Symbol]>, runs: &[Run]) -> bool {
+    let input = plain_text(runs);
+    let report = machine.validate_spans(&input, &spans(runs)).await;
+    if report.is_ok() {
+        info!("Validation passed: {report}");
+        return true;
+    }
+    error!("Validation failed: {report}");
+    for unknown in machine.unknown_characters(&input) {
+        error!("{unknown}");
+    }
+    for overrun in machine.overruns(&input) {
+        error!("{overrun}");
     }
+    false
 }
 
-async fn print_file(machine: &mut Machine<SenderWrapper, &'static [Symbol]>, file_path: &str) {
-    let content = fs::read_to_string(file_path).unwrap();
-    machine.print(&content).await;
+/// The Hal with the transport chosen by the arguments.
+enum Runner {
+    Serial(Hal<SerialTransport>),
+    Dump(Hal<FileDump>),
+    Tcp(Hal<TcpTransport>),
+}
+
+impl Runner {
+    fn new(args: &Args, rx: mpsc::UnboundedReceiver<Instruction>) -> Self {
+        match (&args.serial, &args.dump, args.ip) {
+            (Some(path), _, _) => {
+                let transport = SerialTransport::new(path, args.confirmation)
+                    .with_timeout(Duration::from_millis(args.echo_timeout))
+                    .with_retries(args.retries);
+                Runner::Serial(Hal::new(rx, transport))
+            }
+            (None, Some(path), _) => Runner::Dump(Hal::new(rx, FileDump::new(path))),
+            (None, None, ip) => {
+                let ip = ip.expect("IP address is required");
+                let addr = SocketAddr::new(ip.into(), 1234);
+                let mut transport = TcpTransport::new(addr)
+                    .with_timeout(Duration::from_millis(args.echo_timeout))
+                    .with_retries(args.retries);
+                if let Some(attempts) = args.connect_attempts {
+                    transport = transport.with_connect_attempts(attempts);
+                }
+                Runner::Tcp(Hal::new(rx, transport))
+            }
+        }
+    }
+
+    fn progress(&self) -> watch::Receiver<usize> {
+        match self {
+            Runner::Serial(hal) => hal.progress(),
+            Runner::Dump(hal) => hal.progress(),
+            Runner::Tcp(hal) => hal.progress(),
+        }
+    }
+}
+
+/// Runs the Hal in the background until the Machine halts it.
+fn spawn_runner<T: Transport + Send + 'static>(mut runner: Hal<T>) -> JoinHandle<()> {
+    tokio::task::spawn(async move {
+        info!("the runner is starting");
+        if let Err(e) = runner.run().await {
+            error!("{e:#}");
+            // nothing more can be printed, the Machine is stopped as well
+            std::process::exit(1);
+        }
+        info!("the runner is finished");
+    })
 }
 
 #[tokio::main]
@@ -59,25 +405,70 @@ async fn main() {
 
     let args = Args::parse();
 
-    let handle = tokio::task::spawn(async move {
-        info!("the runner is starting");
-        let addr = SocketAddr::new(args.ip.into(), 1234);
-        let mut runner = Hal::new(rx, addr);
-        let _ = runner.run().await;
-        info!("the runner is finished");
+    if let Some(Command::Disasm { path, wheel }) = &args.command {
+        let data = fs::read(path).unwrap();
+        if let Err(e) = disassemble(&data, wheel.symbols(), &mut io::stdout()) {
+            error!("Cannot write the commands: {e}");
+        }
+        return;
+    }
+    if let Err(e) = args.check() {
+        e.exit();
+    }
+    info!("Machine is starting up");
+    let runner = Runner::new(&args, rx);
+    let sender = SenderWrapper::new(tx).with_progress(runner.progress());
+    let db: &'static [Symbol] = args.wheel.symbols();
+    let mut machine = Machine::new(sender, db);
+    machine.settings_mut().bidirectional = args.bidirectional;
+    machine.settings_mut().unknown_characters = args.unknown_character_policy();
+    let res = args.resolution();
+    machine.set_resolution(res);
+    machine.settings_mut().left_margin = args.left_margin * res.x;
+    machine.settings_mut().right_margin = args
+        .right_margin
+        .map_or(DEFAULT_CARRIAGE_TRAVEL, |column| column * res.x);
+    machine.settings_mut().word_wrap = !args.no_wrap;
+    machine.settings_mut().alignment = args.align.into();
+    machine.settings_mut().tab_stops = args.tab_stops * res.x;
+    machine.settings_mut().page = args.page();
+    machine.settings_mut().pauses = args.pauses();
+
+    let runs = args.text.as_ref().map(|path| {
+        let content = fs::read_to_string(path).unwrap();
+        if args.markup {
+            parse(&content)
+        } else {
+            vec![Run::plain(content)]
+        }
     });
+    if let Some(ref runs) = runs {
+        if !args.skip_validation && !validate(&machine, runs).await {
+            return;
+        }
+    }
 
-    info!("Machine is starting up");
-    let db: &'static [Symbol] = &gabriele::wheels::standard::SYMBOLS;
-    let mut machine = Machine::new(SenderWrapper(tx), db);
+    let handle = match runner {
+        Runner::Serial(hal) => spawn_runner(hal),
+        Runner::Dump(hal) => spawn_runner(hal),
+        Runner::Tcp(hal) => spawn_runner(hal),
+    };
 
+    let activate = args.activate || args.serial.is_some();
+    if activate {
+        machine.connect().await;
+        machine.start().await;
+    }
     machine.offset(4 * 12).await;
 
-    match args.text {
-        Some(path) => print_file(&mut machine, &path).await,
-        None => standard_in(&mut machine).await,
+    match runs {
+        Some(runs) => print_runs(&mut machine, &runs).await,
+        None => standard_in(&mut machine, args.markup).await,
     };
 
+    if activate {
+        machine.stop().await;
+    }
     machine.shutdown().await;
     _ = tokio::join!(handle);
 }
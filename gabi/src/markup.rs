@@ -0,0 +1,125 @@
+use gabriele::attributes::{Attributes, Span};
+use gabriele::layout::Alignment;
+
+/// A piece of text printed with the same attributes and alignment,
+/// the text without the `alignment` is aligned as the whole document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub text: String,
+    pub attributes: Attributes,
+    pub alignment: Option<Alignment>,
+}
+
+impl Run {
+    /// The text printed as it is.
+    pub fn plain(text: String) -> Self {
+        Self {
+            text,
+            attributes: Default::default(),
+            alignment: Default::default(),
+        }
+    }
+
+    /// The place of the run in the plain text, see `Machine::print_spans`.
+    pub fn span(&self) -> Span {
+        Span {
+            len: self.text.len(),
+            attributes: self.attributes,
+            alignment: self.alignment,
+        }
+    }
+}
+
+/// Parses the markup into the runs to be printed one after another:
+/// - `**bold**` is printed with double strikes;
+/// - `_underline_` is overstruck with `_`;
+/// - the lines beginning with `#` and a whitespace are headings, they are centered;
+/// - `-> line` is aligned to the right and `-> line <-` is centered;
+/// - the lines between `::: left`, `center`, `right` or `justify` and `:::` are aligned so;
+/// - `\` makes the next character printed as it is.
+pub fn parse(input: &str) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    let mut attributes = Attributes::default();
+    let mut block = None;
+
+    for line in input.split_inclusive('\n') {
+        if let Some(alignment) = fence(line) {
+            block = alignment;
+            continue;
+        }
+        let (line, alignment) = match (heading(line), arrows(line)) {
+            (Some(text), _) => (String::from(text), Some(Alignment::Center)),
+            (_, Some((text, alignment))) => (text, Some(alignment)),
+            _ => (String::from(line), block),
+        };
+
+        let mut chars = line.chars().peekable();
+        while let Some(chr) = chars.next() {
+            let chr = match chr {
+                '\\' => chars.next().unwrap_or(chr),
+                '*' if chars.next_if_eq(&'*').is_some() => {
+                    attributes.bold = !attributes.bold;
+                    continue;
+                }
+                '_' => {
+                    attributes.underline = !attributes.underline;
+                    continue;
+                }
+                _ => chr,
+            };
+            match runs.last_mut() {
+                Some(run) if run.attributes == attributes && run.alignment == alignment => {
+                    run.text.push(chr)
+                }
+                _ => runs.push(Run {
+                    text: String::from(chr),
+                    attributes,
+                    alignment,
+                }),
+            }
+        }
+    }
+    runs
+}
+
+/// The text of all the runs without any markup.
+pub fn plain_text(runs: &[Run]) -> String {
+    runs.iter().map(|run| run.text.as_str()).collect()
+}
+
+/// The spans of all the runs in the plain text, see `Machine::print_spans`.
+pub fn spans(runs: &[Run]) -> Vec<Span> {
+    runs.iter().map(Run::span).collect()
+}
+
+/// The alignment of a block, which is `None` at the end of the block.
+fn fence(line: &str) -> Option<Option<Alignment>> {
+    match line.trim_end().strip_prefix(":::")?.trim() {
+        "" => Some(None),
+        "left" => Some(Some(Alignment::Left)),
+        "center" => Some(Some(Alignment::Center)),
+        "right" => Some(Some(Alignment::Right)),
+        "justify" => Some(Some(Alignment::Justify)),
+        _ => None,
+    }
+}
+
+fn arrows(line: &str) -> Option<(String, Alignment)> {
+    let (body, newline) = match line.strip_suffix('\n') {
+        Some(body) => (body, "\n"),
+        None => (line, ""),
+    };
+    let body = body.strip_prefix("->")?.trim_start();
+    match body.strip_suffix("<-") {
+        Some(body) => Some((format!("{}{newline}", body.trim_end()), Alignment::Center)),
+        None => Some((format!("{body}{newline}"), Alignment::Right)),
+    }
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    if text.len() == line.len() {
+        return None;
+    }
+    text.strip_prefix(' ')
+}
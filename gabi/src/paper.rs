@@ -0,0 +1,227 @@
+use crate::disasm::petal_character;
+use gabriele::cmd::{Cmd, CmdJump, CmdMotionDirection, Impression, InvalidCommand};
+use gabriele::resolution::Resolution;
+use gabriele::symbol::{AfterSymbolPrinted, Symbol};
+use std::fmt::Write;
+
+/// Horizontal typewriter units per inch.
+pub const X_UNITS_PER_INCH: i32 = 120;
+
+/// Vertical typewriter units per inch.
+pub const Y_UNITS_PER_INCH: i32 = 96;
+
+/// Blank space around the rendered image in inches.
+const SVG_MARGIN: f32 = 0.5;
+
+/// A character struck on the paper at `x` and `y` in typewriter units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Strike {
+    pub x: i32,
+    pub y: i32,
+    pub character: char,
+    pub impression: Impression,
+}
+
+/// Virtual carriage and platen which interpret the commands
+/// the same way the typewriter does and keep record of every strike.
+pub struct Paper<'a> {
+    wheel: &'a [Symbol],
+    res: Resolution,
+    x: i32,
+    y: i32,
+    strikes: Vec<Strike>,
+}
+
+impl<'a> Paper<'a> {
+    pub fn new(wheel: &'a [Symbol], res: Resolution) -> Self {
+        Self {
+            wheel,
+            res,
+            x: 0,
+            y: 0,
+            strikes: Vec::new(),
+        }
+    }
+
+    pub fn strikes(&self) -> &[Strike] {
+        &self.strikes
+    }
+
+    /// Decodes and applies a 2-byte word.
+    pub fn feed(&mut self, word: u16) -> Result<(), InvalidCommand> {
+        self.apply(&Cmd::from_u16(word)?);
+        Ok(())
+    }
+
+    pub fn apply(&mut self, cmd: &Cmd) {
+        match cmd {
+            Cmd::Motion(motion) => {
+                let value = motion.value as i32;
+                match motion.dir {
+                    CmdMotionDirection::PlusX => self.x += value,
+                    CmdMotionDirection::MinusX => self.x -= value,
+                    CmdMotionDirection::PlusY => self.y += value,
+                    CmdMotionDirection::MinusY => self.y -= value,
+                }
+            }
+            Cmd::Jump(CmdJump::Plus) => self.x += self.res.x,
+            Cmd::Jump(CmdJump::Minus) => self.x -= self.res.x,
+            // the session commands leave nothing on the paper
+            Cmd::Jump(_) => (),
+            Cmd::SymbolLow(sym) | Cmd::SymbolHigh(sym) => {
+                let petal = cmd.petal().unwrap_or(sym.code);
+                self.strikes.push(Strike {
+                    x: self.x,
+                    y: self.y,
+                    character: petal_character(self.wheel, petal).unwrap_or('\u{fffd}'),
+                    impression: sym.attr.impression,
+                });
+                match sym.attr.direction {
+                    AfterSymbolPrinted::MoveRight => self.x += self.res.x,
+                    AfterSymbolPrinted::MoveLeft => self.x -= self.res.x,
+                    AfterSymbolPrinted::HoldOn => (),
+                }
+            }
+        }
+    }
+
+    /// Renders the page as a character grid, one cell per character place and line.
+    ///
+    /// Every line of the grid is followed by extra lines for the overstrikes,
+    /// holding the second, third etc. character struck in the same cell.
+    pub fn render_text(&self) -> String {
+        let cell = |strike: &Strike| {
+            (
+                strike.y.div_euclid(self.res.y),
+                strike.x.div_euclid(self.res.x),
+            )
+        };
+        let min_col = self
+            .strikes
+            .iter()
+            .map(|s| cell(s).1)
+            .min()
+            .unwrap_or(0)
+            .min(0);
+        // the strikes above the first line, e.g. a superscript, shift the grid down
+        let min_row = self
+            .strikes
+            .iter()
+            .map(|s| cell(s).0)
+            .min()
+            .unwrap_or(0)
+            .min(0);
+        let rows = self
+            .strikes
+            .iter()
+            .map(|s| cell(s).0)
+            .max()
+            .map_or(0, |r| r + 1 - min_row);
+
+        // layers[row][layer][col]
+        let mut layers: Vec<Vec<Vec<char>>> = vec![Vec::new(); rows.max(0) as usize];
+        for strike in &self.strikes {
+            let (row, col) = cell(strike);
+            let col = (col - min_col) as usize;
+            let row = &mut layers[(row - min_row) as usize];
+            let layer = match row
+                .iter_mut()
+                .find(|layer| layer.get(col).is_none_or(|c| *c == ' '))
+            {
+                Some(layer) => layer,
+                None => {
+                    row.push(Vec::new());
+                    row.last_mut().unwrap()
+                }
+            };
+            if layer.len() <= col {
+                layer.resize(col + 1, ' ');
+            }
+            layer[col] = strike.character;
+        }
+
+        let mut output = String::new();
+        for row in layers {
+            if row.is_empty() {
+                output.push('\n');
+            }
+            for layer in row {
+                output.extend(layer);
+                output.push('\n');
+            }
+        }
+        output
+    }
+
+    /// Renders the page as an SVG image with every strike at its exact position.
+    ///
+    /// The coordinates are in points, the darkness of the ink follows the impression
+    /// and the overstrikes are drawn over each other in the order they were struck.
+    pub fn render_svg(&self) -> String {
+        let to_pt_x = |x: i32| 72.0 * x as f32 / X_UNITS_PER_INCH as f32;
+        let to_pt_y = |y: i32| 72.0 * y as f32 / Y_UNITS_PER_INCH as f32;
+        let margin = 72.0 * SVG_MARGIN;
+
+        let min_x = self.strikes.iter().map(|s| s.x).min().unwrap_or(0).min(0);
+        let min_y = self.strikes.iter().map(|s| s.y).min().unwrap_or(0).min(0);
+        let max_x = self
+            .strikes
+            .iter()
+            .map(|s| s.x + self.res.x)
+            .max()
+            .unwrap_or(0);
+        let max_y = self
+            .strikes
+            .iter()
+            .map(|s| s.y + self.res.y)
+            .max()
+            .unwrap_or(0);
+        let width = to_pt_x(max_x - min_x) + 2.0 * margin;
+        let height = to_pt_y(max_y - min_y) + 2.0 * margin;
+        // monospace glyphs are 0.6 em wide
+        let font_size = to_pt_x(self.res.x) / 0.6;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}pt" height="{height}pt" viewBox="0 0 {width} {height}">"#
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let _ = writeln!(
+            svg,
+            r#"<g font-family="Courier New, Courier, monospace" font-size="{font_size}" fill="black">"#
+        );
+        for strike in &self.strikes {
+            let x = to_pt_x(strike.x - min_x) + margin;
+            let y = to_pt_y(strike.y - min_y + self.res.y) + margin;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x}" y="{y}" fill-opacity="{}">{}</text>"#,
+                ink(strike.impression),
+                escape(strike.character)
+            );
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+/// Darkness of the ink left by the impression.
+fn ink(impression: Impression) -> f32 {
+    match impression {
+        Impression::Mild => 0.5,
+        Impression::Normal => 0.75,
+        Impression::Strong => 0.9,
+        Impression::Strongest => 1.0,
+    }
+}
+
+fn escape(chr: char) -> String {
+    match chr {
+        '&' => String::from("&amp;"),
+        '<' => String::from("&lt;"),
+        '>' => String::from("&gt;"),
+        '"' => String::from("&quot;"),
+        _ => String::from(chr),
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::{bail, Context};
+use clap::ValueEnum;
+use log::{debug, warn};
+use serialport::SerialPort;
+use std::io::ErrorKind;
+use std::thread;
+use std::time::{Duration, Instant};
+use tcp_client::{Stalled, Status, Transport, DEFAULT_ECHO_TIMEOUT};
+
+/// The speed of the typewriter's UART.
+pub const BAUD_RATE: u32 = 4800;
+
+/// How the typewriter confirms every byte it has received.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Confirmation {
+    /// The CTS line is released once the typewriter has taken the byte,
+    /// see the latch wiring in `docs/README.md`
+    #[default]
+    Cts,
+    /// The byte is sent back, as the bridge firmware does
+    Echo,
+}
+
+/// Talks straight to the typewriter's UART through a serial device,
+/// for example an FT232RL USB-UART adapter.
+pub struct SerialTransport {
+    path: String,
+    confirmation: Confirmation,
+    port: Option<Box<dyn SerialPort>>,
+    timeout: Duration,
+    retries: u32,
+    stalled: bool,
+}
+
+impl SerialTransport {
+    pub fn new(path: impl Into<String>, confirmation: Confirmation) -> Self {
+        SerialTransport {
+            path: path.into(),
+            confirmation,
+            port: None,
+            timeout: DEFAULT_ECHO_TIMEOUT,
+            retries: 0,
+            stalled: false,
+        }
+    }
+
+    /// The time to wait for the confirmation of every byte.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How many times more to wait for a missing confirmation before giving up.
+    /// The byte is not sent again, it may have been printed already.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+}
+
+impl Transport for SerialTransport {
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        let port = serialport::new(&self.path, BAUD_RATE)
+            .timeout(self.timeout)
+            .open()
+            .with_context(|| format!("cannot open serial port {}", self.path))?;
+        debug!("serial port {} is open", self.path);
+        self.port = Some(port);
+        self.stalled = false;
+        Ok(())
+    }
+
+    /// The serial port is blocking, every byte is sent from the blocking thread pool.
+    async fn send(&mut self, byte: u8) -> anyhow::Result<()> {
+        let Some(mut port) = self.port.take() else {
+            bail!("cannot transmit bytes, serial port is closed");
+        };
+        let (confirmation, timeout, retries) = (self.confirmation, self.timeout, self.retries);
+        let (port, result) = tokio::task::spawn_blocking(move || {
+            let result = transmit_byte(port.as_mut(), byte, confirmation, timeout, retries);
+            (port, result)
+        })
+        .await
+        .context("serial port runner panicked")?;
+        self.port = Some(port);
+        self.stalled = result.as_ref().is_err_and(|e| e.is::<Stalled>());
+        result
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        if self.port.take().is_some() {
+            debug!("serial port {} is closed", self.path);
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> Status {
+        match (&self.port, self.stalled) {
+            (_, true) => Status::Stalled,
+            (Some(_), false) => Status::Connected,
+            (None, false) => Status::Disconnected,
+        }
+    }
+}
+
+/// Writes a single byte and waits until the typewriter confirms it,
+/// the confirmation is waited for `timeout` once and then `retries` times more.
+fn transmit_byte(
+    port: &mut dyn SerialPort,
+    byte: u8,
+    confirmation: Confirmation,
+    timeout: Duration,
+    retries: u32,
+) -> anyhow::Result<()> {
+    port.write_all(&[byte]).context("cannot transmit bytes")?;
+    if confirmation == Confirmation::Cts {
+        // the latch is set by the start bit, the byte has to be out first
+        port.flush().context("cannot transmit bytes")?;
+    }
+    let mut attempts = 0;
+    loop {
+        let confirmed = match confirmation {
+            Confirmation::Cts => wait_for_cts(port, timeout)?,
+            Confirmation::Echo => read_echo(port, byte)?,
+        };
+        if confirmed {
+            return Ok(());
+        }
+        if attempts == retries {
+            let waited = timeout * (attempts + 1);
+            return Err(Stalled { byte, waited }.into());
+        }
+        attempts += 1;
+        warn!(
+            "No confirmation for {byte:02x} within {timeout:?}, waiting again {attempts}/{retries}"
+        );
+    }
+}
+
+/// Reads the echo of the `byte`, returns `false` when it has not come within the port timeout.
+fn read_echo(port: &mut dyn SerialPort, byte: u8) -> anyhow::Result<bool> {
+    let mut reply = [0u8];
+    match port.read_exact(&mut reply) {
+        Err(e) if e.kind() == ErrorKind::TimedOut => return Ok(false),
+        result => result.context("no echo from the typewriter")?,
+    }
+    if reply[0] != byte {
+        bail!("Expected reply is {byte:02x} but received {:02x}", reply[0]);
+    }
+    Ok(true)
+}
+
+/// Polls the CTS line until the latch is released by the typewriter,
+/// returns `false` when it has not been released within the `timeout`.
+fn wait_for_cts(port: &mut dyn SerialPort, timeout: Duration) -> anyhow::Result<bool> {
+    let started = Instant::now();
+    while !port.read_clear_to_send().context("cannot read CTS")? {
+        if started.elapsed() > timeout {
+            return Ok(false);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    Ok(true)
+}
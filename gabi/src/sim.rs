@@ -1,7 +1,9 @@
 use anyhow::Result;
 use clap::Parser;
 use env_logger::{Builder, Target};
-use log::warn;
+use gabi::paper::Paper;
+use gabi::Wheel;
+use log::{error, warn};
 use std::net::{Ipv4Addr, SocketAddrV4};
 use tokio::fs::File;
 use tokio::io;
@@ -22,6 +24,18 @@ struct Args {
     /// Output file name
     #[arg(long, default_value_t = String::from("output.bin"))]
     path: String,
+
+    /// File name for the typed page rendered as text
+    #[arg(long, default_value_t = String::from("page.txt"))]
+    page: String,
+
+    /// Optional file name for the typed page rendered as SVG image
+    #[arg(long)]
+    svg: Option<String>,
+
+    /// The daisy wheel mounted in the simulated typewriter
+    #[arg(long, value_enum, default_value_t = Wheel::Standard)]
+    wheel: Wheel,
 }
 
 #[tokio::main]
@@ -32,7 +46,8 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     warn!("Args: {:?}", args);
 
-    let mut file = File::create(args.path).await?;
+    let mut file = File::create(&args.path).await?;
+    let mut paper = Paper::new(args.wheel.symbols(), Default::default());
 
     let socket_addr = SocketAddrV4::new(args.ip, args.port);
     let listener = TcpListener::bind(socket_addr).await?;
@@ -56,10 +71,17 @@ async fn main() -> Result<()> {
         }
 
         file.write_u16(buf).await?;
+        if let Err(e) = paper.feed(buf) {
+            error!("{e}");
+        }
     }
 
     // Ensure data is pushed to the disk
     file.flush().await?;
+    tokio::fs::write(&args.page, paper.render_text()).await?;
+    if let Some(path) = &args.svg {
+        tokio::fs::write(path, paper.render_svg()).await?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,103 @@
+use anyhow::{bail, Context};
+use log::debug;
+use std::path::PathBuf;
+use tcp_client::{Status, Transport};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Writes the bytes into a file instead of the typewriter,
+/// the dump can be read back with `gabi disasm`.
+pub struct FileDump {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl FileDump {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileDump {
+            path: path.into(),
+            file: None,
+        }
+    }
+}
+
+impl Transport for FileDump {
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        let file = File::create(&self.path)
+            .await
+            .with_context(|| format!("cannot create {}", self.path.display()))?;
+        debug!("dumping the bytes into {}", self.path.display());
+        self.file = Some(file);
+        Ok(())
+    }
+
+    async fn send(&mut self, byte: u8) -> anyhow::Result<()> {
+        let Some(file) = self.file.as_mut() else {
+            bail!("cannot transmit bytes, {} is closed", self.path.display());
+        };
+        file.write_u8(byte).await.context("cannot write the dump")
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await.context("cannot write the dump")?;
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> Status {
+        match self.file {
+            Some(_) => Status::Connected,
+            None => Status::Disconnected,
+        }
+    }
+}
+
+/// Confirms every byte at once and hands it over to the receiver
+/// returned by `Loopback::new`, the channel is closed on disconnect.
+pub struct Loopback {
+    tx: Option<UnboundedSender<u8>>,
+    connected: bool,
+}
+
+impl Loopback {
+    pub fn new() -> (Self, UnboundedReceiver<u8>) {
+        let (tx, rx) = unbounded_channel();
+        let loopback = Loopback {
+            tx: Some(tx),
+            connected: false,
+        };
+        (loopback, rx)
+    }
+}
+
+impl Transport for Loopback {
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        if self.tx.is_none() {
+            bail!("loopback channel is closed");
+        }
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn send(&mut self, byte: u8) -> anyhow::Result<()> {
+        match (&self.tx, self.connected) {
+            (Some(tx), true) => tx.send(byte).context("loopback channel is closed"),
+            _ => bail!("cannot transmit bytes, loopback is disconnected"),
+        }
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        self.connected = false;
+        self.tx = None;
+        Ok(())
+    }
+
+    fn status(&self) -> Status {
+        match self.connected {
+            true => Status::Connected,
+            false => Status::Disconnected,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use gabriele::machine::{InstructionSender, Machine};
+use gabriele::printing::Instruction;
+use gabriele::symbol::Symbol;
+use std::cell::Cell;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Passes the instructions to the Recorder and counts the paper changes.
+pub struct Tape {
+    tx: UnboundedSender<Instruction>,
+    paper_changes: Cell<usize>,
+}
+
+impl InstructionSender for Tape {
+    async fn send(&self, instr: Instruction) {
+        self.tx.send(instr).expect("cannot send instruction");
+    }
+
+    async fn change_paper(&self) {
+        self.paper_changes.set(self.paper_changes.get() + 1);
+    }
+}
+
+/// Records the instructions produced by the Machine without any transport.
+pub struct Recorder {
+    pub machine: Machine<Tape, &'static [Symbol]>,
+    rx: UnboundedReceiver<Instruction>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::with_wheel(&gabriele::wheels::standard::SYMBOLS)
+    }
+
+    pub fn with_wheel(db: &'static [Symbol]) -> Self {
+        let (tx, rx) = unbounded_channel();
+        let tape = Tape {
+            tx,
+            paper_changes: Cell::new(0),
+        };
+        let machine = Machine::new(tape, db);
+        Self { machine, rx }
+    }
+
+    /// Takes all the instructions recorded so far.
+    pub fn instructions(&mut self) -> Vec<Instruction> {
+        let mut result = Vec::new();
+        while let Ok(instr) = self.rx.try_recv() {
+            result.push(instr);
+        }
+        result
+    }
+
+    /// Number of times the Machine has asked for a new sheet.
+    pub fn paper_changes(&self) -> usize {
+        self.machine.sender().paper_changes.get()
+    }
+}
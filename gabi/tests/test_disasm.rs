@@ -0,0 +1,37 @@
+use gabi::disasm::{describe, disassemble};
+use gabriele::wheels::standard::SYMBOLS;
+
+#[test]
+fn describes_symbols_with_characters() {
+    let word = u16::from_be_bytes([36, 0x80 | 31]);
+    assert_eq!(
+        describe(2, word, &SYMBOLS),
+        "000002  249f  symbol   36 'A'   normal move right"
+    );
+}
+
+#[test]
+fn disassembles_reference_output() {
+    let mut out = Vec::new();
+    disassemble(include_bytes!("../ref_output.bin"), &SYMBOLS, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("000000  c030  motion  +x 48"));
+    assert_eq!(
+        lines.next(),
+        Some("000002  2f9f  symbol   47 'I'   normal move right")
+    );
+    assert!(!text.contains("invalid"));
+}
+
+#[test]
+fn reports_incomplete_words() {
+    let mut out = Vec::new();
+    disassemble(&[0xc0, 0x30, 0x83], &SYMBOLS, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        text,
+        "000000  c030  motion  +x 48\n000002  83    incomplete word\n"
+    );
+}
@@ -0,0 +1,704 @@
+mod helpers;
+
+use crate::helpers::recorder::Recorder;
+use gabriele::attributes::{Attributes, Script, Span};
+use gabriele::cmd::{Cmd, CmdMotion, Impression};
+use gabriele::database::{UnknownCharacter, UnknownCharacterPolicy};
+use gabriele::layout::Alignment;
+use gabriele::machine::PrintingDirection;
+use gabriele::page::Page;
+use gabriele::pause::Pauses;
+use gabriele::position::Position;
+use gabriele::printing::Instruction;
+use gabriele::resolution::{
+    LineSpacing, Pitch, Resolution, DEFAULT_X_RESOLUTION as X_RES, DEFAULT_Y_RESOLUTION as Y_RES,
+};
+use gabriele::symbol::{AfterSymbolPrinted, CmdSymbol, Symbol, SymbolPrintingAttrs};
+use gabriele::validation::Overrun;
+use gabriele::Error;
+
+fn strike(code: u8, direction: AfterSymbolPrinted) -> Instruction {
+    let attr = SymbolPrintingAttrs {
+        direction,
+        impression: Impression::Normal,
+    };
+    Cmd::SymbolLow(CmdSymbol { code, attr }).as_instruction()
+}
+
+fn motion(cmd: CmdMotion) -> Instruction {
+    Cmd::Motion(cmd).as_instruction()
+}
+
+#[tokio::test]
+async fn prints_every_other_line_leftwards() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().bidirectional = true;
+
+    rec.machine.print("AT\nTA\nA\n").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        // no carriage return, the carriage stops at the last character of "TA"
+        motion(CmdMotion::minus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::MoveLeft),
+        strike(37, AfterSymbolPrinted::MoveLeft),
+        motion(CmdMotion::plus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        // the last line feed has nothing to look ahead to
+        motion(CmdMotion::minus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+
+    let expected_position = Position {
+        x: 0,
+        y: 3 * Y_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn keeps_blank_lines_rightwards() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().bidirectional = true;
+
+    rec.machine.print("A\n\n\nA \n").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::MoveRight),
+        // the trailing whitespace is not printed leftwards
+        motion(CmdMotion::minus_x(x)),
+        motion(CmdMotion::plus_y(3 * y)),
+        strike(36, AfterSymbolPrinted::MoveLeft),
+        motion(CmdMotion::plus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn aborts_on_unknown_characters() {
+    let mut rec = Recorder::new();
+
+    let input = "A€\nTA€";
+    let err = rec.machine.print(input).await.unwrap_err();
+
+    let Error::UnknownCharacters(err) = err else {
+        panic!("unexpected error {err}");
+    };
+    assert_eq!(err.count, 2);
+    let unknown: Vec<UnknownCharacter> = rec.machine.unknown_characters(input).collect();
+    let expected = vec![
+        UnknownCharacter {
+            character: '€',
+            line: 1,
+            column: 2,
+        },
+        UnknownCharacter {
+            character: '€',
+            line: 2,
+            column: 3,
+        },
+    ];
+    assert_eq!(unknown, expected);
+    assert_eq!(rec.instructions(), vec![]);
+    assert_eq!(rec.machine.current_position(), Position::default());
+}
+
+#[tokio::test]
+async fn substitutes_unknown_characters() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().unknown_characters = UnknownCharacterPolicy::Substitute('?');
+
+    rec.machine.print("A€").await.unwrap();
+
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(45, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn validates_without_sending() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().carriage_travel = 3 * X_RES;
+
+    let input = "AT\nTAT€\nATTA\n";
+    let report = rec.machine.validate(input).await;
+
+    assert_eq!(report.unknown_characters, 1);
+    assert_eq!(report.overruns, 1);
+    // 9 strikes and 3 line feeds with the carriage and paper motion each
+    assert_eq!(report.instructions, 9 + 3 * 2);
+    assert!(!report.is_ok());
+
+    let overruns: Vec<Overrun> = rec.machine.overruns(input).collect();
+    let expected = vec![Overrun {
+        line: 3,
+        end: 4 * X_RES,
+    }];
+    assert_eq!(overruns, expected);
+
+    assert_eq!(rec.instructions(), vec![]);
+    assert_eq!(rec.machine.current_position(), Position::default());
+}
+
+#[tokio::test]
+async fn wraps_words_between_the_margins() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().left_margin = X_RES;
+    rec.machine.settings_mut().right_margin = 3 * X_RES;
+
+    rec.machine.print("AT TA").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        // the first row begins at the left margin as well
+        motion(CmdMotion::plus_x(x)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        // the whitespace at the break is dropped, the next row begins at the left margin
+        motion(CmdMotion::minus_x(2 * x)),
+        motion(CmdMotion::plus_y(y)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        strike(36, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+
+    let expected_position = Position {
+        x: 3 * X_RES,
+        y: Y_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn counts_the_sheets_without_sending() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().page = Some(Page {
+        length: 5 * Y_RES,
+        top_margin: Y_RES,
+        bottom_margin: 2 * Y_RES,
+    });
+
+    // two lines fit between the margins of every sheet
+    let report = rec.machine.validate("A\nT\nA\nT\nA\n").await;
+
+    assert_eq!(report.sheets, 3);
+    assert!(report.is_ok());
+    assert_eq!(rec.paper_changes(), 0);
+    assert_eq!(rec.instructions(), vec![]);
+
+    let report = Recorder::new().machine.validate("A\nT\n").await;
+    assert_eq!(report.sheets, 0);
+}
+
+#[tokio::test]
+async fn validates_the_spans_as_printed() {
+    let mut rec = Recorder::new();
+    let bold = Attributes {
+        bold: true,
+        ..Default::default()
+    };
+    let spans = [Span {
+        len: 1,
+        attributes: bold,
+        alignment: None,
+    }];
+
+    let report = rec.machine.validate_spans("AT", &spans).await;
+    rec.machine.print_spans("AT", &spans).await.unwrap();
+
+    // the bold character is struck twice with the motions around the second strike
+    assert_eq!(report.instructions, 5);
+    assert_eq!(report.instructions, rec.instructions().len());
+}
+
+#[tokio::test]
+async fn breaks_the_page_when_it_is_full() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().page = Some(Page {
+        length: 5 * Y_RES,
+        top_margin: Y_RES,
+        bottom_margin: 2 * Y_RES,
+    });
+
+    rec.machine.print("A\nT\nA").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        // the first line is printed at the top margin
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+        // the sheet is ejected and the new one is fed to the top margin
+        motion(CmdMotion::plus_y(2 * y)),
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+    assert_eq!(rec.paper_changes(), 1);
+
+    let expected_position = Position {
+        x: X_RES,
+        y: Y_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn overprints_after_carriage_return_and_backspace() {
+    let mut rec = Recorder::new();
+
+    rec.machine.print("AT\r--\x08\x08").await.unwrap();
+
+    let x = X_RES as u16;
+    let dash = Cmd::SymbolLow(CmdSymbol {
+        code: 3,
+        attr: SymbolPrintingAttrs {
+            direction: AfterSymbolPrinted::MoveRight,
+            impression: Impression::Mild,
+        },
+    })
+    .as_instruction();
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        // the carriage returns without moving the paper
+        motion(CmdMotion::minus_x(2 * x)),
+        dash,
+        dash,
+        motion(CmdMotion::minus_x(2 * x)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+    assert_eq!(rec.machine.current_position(), Position::default());
+}
+
+#[tokio::test]
+async fn prints_bold_runs_with_double_strikes() {
+    let mut rec = Recorder::new();
+
+    rec.machine.attributes_mut().bold = true;
+    rec.machine.print("A").await.unwrap();
+    rec.machine.attributes_mut().bold = false;
+    rec.machine.print("T").await.unwrap();
+
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(1)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(1)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+
+    let expected_position = Position {
+        x: 2 * X_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn underlines_runs_including_whitespace() {
+    let mut rec = Recorder::new();
+
+    rec.machine.attributes_mut().underline = true;
+    rec.machine.print("A T").await.unwrap();
+
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+
+    let expected_position = Position {
+        x: 3 * X_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn strikes_through_leftwards() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().direction = PrintingDirection::Left;
+
+    rec.machine.attributes_mut().strikethrough = true;
+    rec.machine.print("A").await.unwrap();
+
+    let dash = Cmd::SymbolLow(CmdSymbol {
+        code: 3,
+        attr: SymbolPrintingAttrs {
+            direction: AfterSymbolPrinted::MoveLeft,
+            impression: Impression::Mild,
+        },
+    })
+    .as_instruction();
+    let expected = vec![strike(36, AfterSymbolPrinted::HoldOn), dash];
+    assert_eq!(rec.instructions(), expected);
+
+    let expected_position = Position {
+        x: -X_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn prints_superscript_and_subscript_off_the_baseline() {
+    let mut rec = Recorder::new();
+
+    rec.machine.attributes_mut().script = Script::Superscript;
+    rec.machine.print("A").await.unwrap();
+    rec.machine.attributes_mut().script = Script::Subscript;
+    rec.machine.print("T").await.unwrap();
+    rec.machine.attributes_mut().script = Script::Baseline;
+    rec.machine.print("A").await.unwrap();
+
+    let half = Y_RES as u16 / 2;
+    let expected = vec![
+        motion(CmdMotion::minus_y(half)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::plus_y(half)),
+        motion(CmdMotion::plus_y(half)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_y(half)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+
+    let expected_position = Position {
+        x: 3 * X_RES,
+        ..Default::default()
+    };
+    assert_eq!(rec.machine.current_position(), expected_position);
+}
+
+#[tokio::test]
+async fn centers_rows_between_the_margins() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 6 * X_RES;
+    rec.machine.settings_mut().alignment = Alignment::Center;
+
+    rec.machine.print("AT\n").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        motion(CmdMotion::plus_x(2 * x)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(4 * x)),
+        motion(CmdMotion::plus_y(y)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn moves_by_the_proportional_widths() {
+    let mut rec = Recorder::with_wheel(&gabriele::wheels::proportional::SYMBOLS);
+    rec.machine.settings_mut().right_margin = 40;
+
+    rec.machine.print("lm lm").await.unwrap();
+
+    let expected = vec![
+        strike(5, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(6)),
+        strike(6, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(16)),
+        // the next word does not fit, the carriage returns from 22
+        motion(CmdMotion::minus_x(22)),
+        motion(CmdMotion::plus_y(Y_RES as u16)),
+        strike(5, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(6)),
+        strike(6, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(16)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn justifies_all_rows_but_the_last_one() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 6 * X_RES;
+    rec.machine.settings_mut().alignment = Alignment::Justify;
+
+    rec.machine.print("AT TA AT\n").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        // the whitespace is stretched to reach the right margin
+        motion(CmdMotion::plus_x(2 * x)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(6 * x)),
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(2 * x)),
+        motion(CmdMotion::plus_y(y)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn underlines_the_stretched_gaps() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 6 * X_RES + 5;
+    rec.machine.settings_mut().alignment = Alignment::Justify;
+    rec.machine.attributes_mut().underline = true;
+
+    rec.machine.print("AT TA AT").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        // the gap is stretched by a character place and 5 units, both are underlined
+        strike(51, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(7)),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(36, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(6 * x + 5)),
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::HoldOn),
+        strike(51, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn justifies_rows_with_emphasis_as_a_whole() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 6 * X_RES;
+    rec.machine.settings_mut().alignment = Alignment::Justify;
+
+    let bold = Attributes {
+        bold: true,
+        ..Default::default()
+    };
+    let spans = [Span {
+        len: 2,
+        attributes: bold,
+        alignment: None,
+    }];
+    rec.machine.print_spans("AT TA AT", &spans).await.unwrap();
+
+    let x = X_RES as u16;
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(1)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(1)),
+        strike(37, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(1)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(1)),
+        // the gap is stretched over the whole row, not the bold word alone
+        motion(CmdMotion::plus_x(2 * x)),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        strike(36, AfterSymbolPrinted::MoveRight),
+    ];
+    assert_eq!(rec.instructions()[..expected.len()], expected);
+}
+
+#[tokio::test]
+async fn wraps_the_rows_printed_leftwards() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().bidirectional = true;
+    rec.machine.settings_mut().right_margin = 2 * X_RES;
+
+    rec.machine.print("AT TA\n").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+        strike(36, AfterSymbolPrinted::MoveLeft),
+        strike(37, AfterSymbolPrinted::MoveLeft),
+        motion(CmdMotion::plus_x(x)),
+        motion(CmdMotion::plus_y(y)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn aligns_rows_to_the_right_margin() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 6 * X_RES;
+    rec.machine.settings_mut().alignment = Alignment::Right;
+
+    rec.machine.print("AT\n").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let expected = vec![
+        motion(CmdMotion::plus_x(4 * x)),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        strike(37, AfterSymbolPrinted::MoveRight),
+        motion(CmdMotion::minus_x(6 * x)),
+        motion(CmdMotion::plus_y(y)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn follows_the_pitch_and_line_spacing() {
+    let mut rec = Recorder::new();
+    rec.machine
+        .set_resolution(Resolution::new(Pitch::Elite, LineSpacing::Double));
+
+    rec.machine.print("A  T\nA").await.unwrap();
+
+    let expected = vec![
+        // the carriage is moved explicitly instead of the typewriter's own step
+        strike(36, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(10)),
+        motion(CmdMotion::plus_x(20)),
+        strike(37, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(10)),
+        motion(CmdMotion::minus_x(40)),
+        motion(CmdMotion::plus_y(32)),
+        strike(36, AfterSymbolPrinted::HoldOn),
+        motion(CmdMotion::plus_x(10)),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn splits_the_long_paper_motion() {
+    let mut rec = Recorder::new();
+
+    rec.machine.print(&"\n".repeat(3000)).await.unwrap();
+
+    // 3000 lines are 48000 units, more than a single motion of 32767 units
+    let mut expected = vec![motion(CmdMotion::plus_y(0xfff)); 8];
+    expected.push(motion(CmdMotion::plus_y(7)));
+    expected.extend(vec![motion(CmdMotion::plus_y(0xfff)); 3]);
+    expected.push(motion(CmdMotion::plus_y(2948)));
+    assert_eq!(rec.instructions(), expected);
+    assert_eq!(rec.machine.current_position().y, 3000 * Y_RES);
+}
+
+#[tokio::test]
+async fn aborts_on_petals_beyond_the_wheel() {
+    static BROKEN_WHEEL: [Symbol; 2] = [Symbol::new('A').petal(36), Symbol::new('x').petal(120)];
+    let mut rec = Recorder::with_wheel(&BROKEN_WHEEL);
+
+    let err = rec.machine.print("Ax").await.unwrap_err();
+
+    assert_eq!(err, Error::InvalidPetal(120));
+    assert_eq!(rec.instructions(), vec![]);
+}
+
+#[tokio::test]
+async fn drives_the_session() {
+    let mut rec = Recorder::new();
+
+    rec.machine.connect().await;
+    rec.machine.start().await;
+    rec.machine.print("A").await.unwrap();
+    rec.machine.stop().await;
+
+    let session = |word: u8| Instruction::SendBytes(u16::from_be_bytes([word, 0]));
+    let expected = vec![
+        session(0xa1),
+        session(0xa2),
+        strike(36, AfterSymbolPrinted::MoveRight),
+        session(0xa3),
+        session(0xa0),
+    ];
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn waits_for_the_motions_and_the_new_sheet() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().page = Some(Page {
+        length: 4 * Y_RES,
+        top_margin: 0,
+        bottom_margin: Y_RES,
+    });
+    rec.machine.settings_mut().pauses = Pauses {
+        motion: 50,
+        page: 2000,
+        pacing: 0,
+    };
+
+    rec.machine.print("A\nT\nA\nT").await.unwrap();
+
+    let x = X_RES as u16;
+    let y = Y_RES as u16;
+    let feed = [
+        motion(CmdMotion::minus_x(x)),
+        Instruction::Idle(6),
+        motion(CmdMotion::plus_y(y)),
+        Instruction::Idle(8),
+    ];
+    let mut expected = vec![strike(36, AfterSymbolPrinted::MoveRight)];
+    expected.extend(feed);
+    expected.push(strike(37, AfterSymbolPrinted::MoveRight));
+    expected.extend(feed);
+    expected.push(strike(36, AfterSymbolPrinted::MoveRight));
+    expected.extend(feed);
+    expected.extend([
+        // the sheet is ejected, the next page waits for the operator and the paper
+        motion(CmdMotion::plus_y(y)),
+        Instruction::Idle(8),
+        Instruction::Idle(2000),
+        strike(37, AfterSymbolPrinted::MoveRight),
+    ]);
+    assert_eq!(rec.instructions(), expected);
+}
+
+#[tokio::test]
+async fn aborts_on_the_bold_offset_out_of_range() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().bold_offset = 40_000;
+    rec.machine.attributes_mut().bold = true;
+
+    let result = rec.machine.print("A").await;
+
+    assert!(matches!(result, Err(Error::MotionOutOfRange(_))));
+    assert_eq!(rec.instructions(), vec![]);
+
+    let report = rec.machine.validate("A").await;
+    assert!(matches!(report.error, Some(Error::MotionOutOfRange(_))));
+    assert!(!report.is_ok());
+}
@@ -0,0 +1,133 @@
+mod helpers;
+
+use crate::helpers::recorder::Recorder;
+use gabi::markup::{parse, plain_text, spans, Run};
+use gabriele::attributes::Attributes;
+use gabriele::cmd::{Cmd, CmdMotion};
+use gabriele::layout::Alignment;
+use gabriele::resolution::DEFAULT_X_RESOLUTION as X_RES;
+use gabriele::Error;
+
+fn run(text: &str, bold: bool, underline: bool, alignment: Option<Alignment>) -> Run {
+    Run {
+        text: String::from(text),
+        attributes: Attributes {
+            bold,
+            underline,
+            ..Default::default()
+        },
+        alignment,
+    }
+}
+
+#[test]
+fn parses_bold_and_underline() {
+    let runs = parse("a **b** _c_\n");
+    let expected = vec![
+        run("a ", false, false, None),
+        run("b", true, false, None),
+        run(" ", false, false, None),
+        run("c", false, true, None),
+        run("\n", false, false, None),
+    ];
+    assert_eq!(runs, expected);
+}
+
+#[test]
+fn centers_headings() {
+    let runs = parse("## Title\ntext\n");
+    let expected = vec![
+        run("Title\n", false, false, Some(Alignment::Center)),
+        run("text\n", false, false, None),
+    ];
+    assert_eq!(runs, expected);
+}
+
+#[test]
+fn keeps_escaped_characters() {
+    let runs = parse("\\*\\*a\\_b\\\\\n#1\n");
+    assert_eq!(plain_text(&runs), "**a_b\\\n#1\n");
+    assert!(runs
+        .iter()
+        .all(|run| run.attributes == Attributes::default()));
+}
+
+#[test]
+fn aligns_lines_and_blocks() {
+    let runs = parse("-> 1 May\n->  Title  <-\n::: right\nA\nB\n:::\nC\n");
+    let expected = vec![
+        run("1 May\n", false, false, Some(Alignment::Right)),
+        run("Title\n", false, false, Some(Alignment::Center)),
+        run("A\nB\n", false, false, Some(Alignment::Right)),
+        run("C\n", false, false, None),
+    ];
+    assert_eq!(runs, expected);
+}
+
+#[tokio::test]
+async fn centers_headings_with_emphasis_as_a_whole() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 9 * X_RES;
+
+    let runs = parse("# **AT** TA");
+    let input = plain_text(&runs);
+    rec.machine
+        .print_spans(&input, &spans(&runs))
+        .await
+        .unwrap();
+
+    // "AT TA" leaves 4 character places free, the heading begins 2 of them away
+    let instructions = rec.instructions();
+    let indent = Cmd::Motion(CmdMotion::plus_x(2 * X_RES as u16)).as_instruction();
+    assert_eq!(instructions.first(), Some(&indent));
+    assert_eq!(rec.machine.current_position().x, 7 * X_RES);
+}
+
+#[tokio::test]
+async fn aborts_before_the_first_run_is_printed() {
+    let mut rec = Recorder::new();
+
+    let runs = parse("**AT** \u{263a}\n");
+    let input = plain_text(&runs);
+    let result = rec.machine.print_spans(&input, &spans(&runs)).await;
+
+    assert!(matches!(result, Err(Error::UnknownCharacters(_))));
+    assert_eq!(rec.instructions(), vec![]);
+}
+
+#[tokio::test]
+async fn aligns_lines_with_emphasis_to_the_right_as_a_whole() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 20 * X_RES;
+
+    let runs = parse("-> **1 May** 2024");
+    let input = plain_text(&runs);
+    rec.machine
+        .print_spans(&input, &spans(&runs))
+        .await
+        .unwrap();
+
+    // the date ends at the right margin, not the bold day and month alone
+    let instructions = rec.instructions();
+    let indent = Cmd::Motion(CmdMotion::plus_x(10 * X_RES as u16)).as_instruction();
+    assert_eq!(instructions.first(), Some(&indent));
+    assert_eq!(rec.machine.current_position().x, 20 * X_RES);
+}
+
+#[tokio::test]
+async fn centers_lines_with_underline_as_a_whole() {
+    let mut rec = Recorder::new();
+    rec.machine.settings_mut().right_margin = 9 * X_RES;
+
+    let runs = parse("-> _AT_ TA <-");
+    let input = plain_text(&runs);
+    rec.machine
+        .print_spans(&input, &spans(&runs))
+        .await
+        .unwrap();
+
+    let instructions = rec.instructions();
+    let indent = Cmd::Motion(CmdMotion::plus_x(2 * X_RES as u16)).as_instruction();
+    assert_eq!(instructions.first(), Some(&indent));
+    assert_eq!(rec.machine.current_position().x, 7 * X_RES);
+}
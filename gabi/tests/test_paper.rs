@@ -0,0 +1,73 @@
+use gabi::paper::{Paper, Strike};
+use gabriele::cmd::{Cmd, CmdMotion, Impression};
+use gabriele::wheels::standard::SYMBOLS;
+
+fn render(data: &[u8]) -> String {
+    let mut paper = Paper::new(&SYMBOLS, Default::default());
+    for chunk in data.chunks_exact(2) {
+        paper
+            .feed(u16::from_be_bytes([chunk[0], chunk[1]]))
+            .unwrap();
+    }
+    paper.render_text()
+}
+
+#[test]
+fn renders_reference_page() {
+    let page = render(include_bytes!("../ref_output.bin"));
+    assert_eq!(page, include_str!("../ref_page.txt"));
+}
+
+#[test]
+fn keeps_overstrikes_in_extra_lines() {
+    // "A" with hold-on, "_" over it, then "B" on the next line
+    let page = render(&[36, 31, 51, 0x80 | 31, 0xd0, 16, 28, 0x80 | 31]);
+    assert_eq!(page, "A\n_\n B\n");
+}
+
+#[test]
+fn shifts_the_lines_below_the_strikes_above_the_first_one() {
+    let mut paper = Paper::new(&SYMBOLS, Default::default());
+    // "A", then "B" raised by half a line as a superscript
+    paper.feed(u16::from_be_bytes([36, 0x80 | 31])).unwrap();
+    paper.apply(&Cmd::Motion(CmdMotion::minus_y(8)));
+    paper.feed(u16::from_be_bytes([28, 0x80 | 31])).unwrap();
+
+    assert_eq!(paper.render_text(), " B\nA\n");
+}
+
+#[test]
+fn tracks_the_carriage() {
+    let mut paper = Paper::new(&SYMBOLS, Default::default());
+    paper.apply(&Cmd::Motion(CmdMotion::plus_x(30)));
+    paper.apply(&Cmd::Motion(CmdMotion::plus_y(8)));
+    paper.feed(u16::from_be_bytes([93, 0x80 | 15])).unwrap();
+
+    let expected = Strike {
+        x: 30,
+        y: 8,
+        character: 'e',
+        impression: Impression::Mild,
+    };
+    assert_eq!(paper.strikes(), &[expected]);
+}
+
+#[test]
+fn draws_strikes_at_exact_positions() {
+    let mut paper = Paper::new(&SYMBOLS, Default::default());
+    // "e" with hold-on and a mild grave accent over it, then "&" half a line lower
+    paper.feed(u16::from_be_bytes([93, 31])).unwrap();
+    paper.feed(u16::from_be_bytes([72, 0x80 | 15])).unwrap();
+    paper.apply(&Cmd::Motion(CmdMotion::plus_y(8)));
+    paper.feed(u16::from_be_bytes([34, 0x80 | 63])).unwrap();
+
+    let svg = paper.render_svg();
+    let texts: Vec<&str> = svg.lines().filter(|l| l.starts_with("<text")).collect();
+    let expected = vec![
+        r#"<text x="36" y="48" fill-opacity="0.75">e</text>"#,
+        r#"<text x="36" y="48" fill-opacity="0.5">`</text>"#,
+        r#"<text x="43.2" y="54" fill-opacity="1">&amp;</text>"#,
+    ];
+    assert_eq!(texts, expected);
+    assert!(svg.starts_with("<svg"));
+}
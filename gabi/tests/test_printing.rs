@@ -12,19 +12,21 @@ use gabriele::{
 };
 
 fn hit(impression: Impression, direction: AfterSymbolPrinted) -> u8 {
-    let mut sym = CmdSymbol::default();
-    sym.attr = SymbolPrintingAttrs {
-        direction,
-        impression,
+    let sym = CmdSymbol {
+        attr: SymbolPrintingAttrs {
+            direction,
+            impression,
+        },
+        ..Default::default()
     };
     Cmd::SymbolLow(sym).as_u16().to_be_bytes()[1]
 }
 
 #[tokio::test]
 async fn prints_two_characters() {
-    let mut app = TestApp::run(1234).await;
+    let mut app = TestApp::run().await;
 
-    app.machine.print("AT").await;
+    app.machine.print("AT").await.unwrap();
 
     let hit = hit(Default::default(), Default::default());
 
@@ -52,8 +54,8 @@ async fn prints_two_characters() {
 
 #[tokio::test]
 async fn prints_special_character() {
-    let mut app = TestApp::run(1235).await;
-    app.machine.print("à").await;
+    let mut app = TestApp::run().await;
+    app.machine.print("à").await.unwrap();
 
     let first_hit = hit(Default::default(), AfterSymbolPrinted::HoldOn);
 
@@ -72,7 +74,7 @@ async fn prints_special_character() {
     assert_eq!(byte, second_hit);
 
     let expected_position = Position {
-        x: X_RES * 1,
+        x: X_RES,
         y: 0,
         ..Default::default()
     };
@@ -83,15 +85,15 @@ async fn prints_special_character() {
 
 #[tokio::test]
 async fn prints_character_with_a_newline() {
-    let mut app = TestApp::run(1236).await;
+    let mut app = TestApp::run().await;
 
-    app.machine.print("A\n").await;
-    app.machine.shutdown().await;
+    app.machine.print("A\n").await.unwrap();
+    app.halt().await;
 
     let hit = crate::hit(Default::default(), AfterSymbolPrinted::MoveRight);
 
-    let carriage_motion: Vec<Instruction> = move_relative(-1 * X_RES as i16, 0).collect();
-    let roll_motion: Vec<Instruction> = move_relative(0, 1 * Y_RES as i16).collect();
+    let carriage_motion: Vec<Instruction> = move_relative(-(X_RES as i16), 0).collect();
+    let roll_motion: Vec<Instruction> = move_relative(0, Y_RES as i16).collect();
 
     let byte = app.rx.recv().await.unwrap();
     assert_eq!(byte, 36);
@@ -124,13 +126,13 @@ async fn prints_character_with_a_newline() {
 async fn prints_welcome_file() {
     let _ = env_logger::builder().is_test(true).try_init();
 
-    let mut app = TestApp::run(1237).await;
+    let mut app = TestApp::run().await;
 
     let content = include_str!("../welcome.txt");
     let expected = Bytes::from_static(include_bytes!("../ref_output.bin"));
 
     app.machine.offset(4 * 12).await;
-    app.machine.print(content).await;
+    app.machine.print(content).await.unwrap();
     app.halt().await;
 
     let mut buf = BytesMut::with_capacity(1024);
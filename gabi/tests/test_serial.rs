@@ -0,0 +1,92 @@
+use gabi::{Confirmation, Hal, SenderWrapper, SerialTransport, Stalled, Status, Transport};
+use gabriele::machine::Machine;
+use gabriele::symbol::Symbol;
+use serialport::{SerialPort, TTYPort};
+use std::io::{Read, Write};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::unbounded_channel;
+
+/// Plays the typewriter on the master side of a pseudo-terminal pair,
+/// every byte is answered with the `reply` and collected.
+/// The `master` has to outlive the serial port, the unread replies are lost otherwise.
+fn typewriter(master: &TTYPort, count: usize, reply: fn(u8) -> u8) -> thread::JoinHandle<Vec<u8>> {
+    let mut master = master.try_clone_native().unwrap();
+    master.set_timeout(Duration::from_secs(5)).unwrap();
+    thread::spawn(move || {
+        let mut received = vec![];
+        let mut byte = [0u8];
+        while received.len() < count && master.read_exact(&mut byte).is_ok() {
+            master.write_all(&[reply(byte[0])]).unwrap();
+            received.push(byte[0]);
+        }
+        received
+    })
+}
+
+#[tokio::test]
+async fn sends_the_bytes_confirmed_by_the_echo() {
+    let (master, slave) = TTYPort::pair().unwrap();
+    let typewriter = typewriter(&master, 4, |byte| byte);
+
+    let (sender, receiver) = unbounded_channel();
+    let transport = SerialTransport::new(slave.name().unwrap(), Confirmation::Echo);
+    let mut hal = Hal::new(receiver, transport);
+    let runner = tokio::spawn(async move { hal.run().await });
+    let db: &'static [Symbol] = &gabriele::wheels::standard::SYMBOLS;
+    let mut machine = Machine::new(SenderWrapper::new(sender), db);
+
+    machine.print("AT").await.unwrap();
+    machine.shutdown().await;
+
+    runner.await.unwrap().unwrap();
+    assert_eq!(typewriter.join().unwrap(), vec![36, 159, 37, 159]);
+}
+
+#[tokio::test]
+async fn stops_on_a_wrong_echo() {
+    let (master, slave) = TTYPort::pair().unwrap();
+    let typewriter = typewriter(&master, 1, |byte| !byte);
+
+    let (sender, receiver) = unbounded_channel();
+    let transport = SerialTransport::new(slave.name().unwrap(), Confirmation::Echo);
+    let mut hal = Hal::new(receiver, transport);
+    let runner = tokio::spawn(async move { hal.run().await });
+    let db: &'static [Symbol] = &gabriele::wheels::standard::SYMBOLS;
+    let mut machine = Machine::new(SenderWrapper::new(sender), db);
+
+    machine.print("AT").await.unwrap();
+
+    let err = runner.await.unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "Expected reply is 24 but received db");
+    assert_eq!(typewriter.join().unwrap(), vec![36]);
+}
+
+#[tokio::test]
+async fn reports_the_missing_echo_as_stalled() {
+    // the typewriter never answers, the master is only kept open
+    let (_master, slave) = TTYPort::pair().unwrap();
+
+    let (sender, receiver) = unbounded_channel();
+    let transport = SerialTransport::new(slave.name().unwrap(), Confirmation::Echo)
+        .with_timeout(Duration::from_millis(50))
+        .with_retries(2);
+    let mut hal = Hal::new(receiver, transport);
+    let runner = tokio::spawn(async move {
+        let result = hal.run().await;
+        (hal, result)
+    });
+    let db: &'static [Symbol] = &gabriele::wheels::standard::SYMBOLS;
+    let mut machine = Machine::new(SenderWrapper::new(sender), db);
+
+    machine.print("A").await.unwrap();
+
+    let (hal, result) = runner.await.unwrap();
+    let stalled = result.unwrap_err().downcast::<Stalled>().unwrap();
+    let expected = Stalled {
+        byte: 36,
+        waited: Duration::from_millis(150),
+    };
+    assert_eq!(stalled, expected);
+    assert_eq!(hal.transport().status(), Status::Stalled);
+}
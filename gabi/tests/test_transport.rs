@@ -0,0 +1,131 @@
+use gabi::{FileDump, Hal, Loopback, SenderWrapper, Stalled, Status, TcpTransport, Transport};
+use gabriele::machine::InstructionSender;
+use gabriele::printing::Instruction;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::unbounded_channel;
+
+#[tokio::test]
+async fn loopback_hands_over_the_bytes() {
+    let (mut loopback, mut rx) = Loopback::new();
+    assert!(loopback.send(0x24).await.is_err());
+
+    loopback.connect().await.unwrap();
+    assert_eq!(loopback.status(), Status::Connected);
+    loopback.send(0x24).await.unwrap();
+    loopback.send(0x9f).await.unwrap();
+    loopback.disconnect().await.unwrap();
+
+    assert_eq!(loopback.status(), Status::Disconnected);
+    assert_eq!(rx.recv().await, Some(0x24));
+    assert_eq!(rx.recv().await, Some(0x9f));
+    assert_eq!(rx.recv().await, None);
+}
+
+#[tokio::test]
+async fn file_dump_writes_the_bytes() {
+    let path = std::env::temp_dir().join(format!("gabi-dump-{}.bin", std::process::id()));
+    let mut dump = FileDump::new(&path);
+
+    dump.connect().await.unwrap();
+    for byte in [0xc0, 0x0c, 0x24, 0x9f] {
+        dump.send(byte).await.unwrap();
+    }
+    dump.disconnect().await.unwrap();
+
+    assert_eq!(std::fs::read(&path).unwrap(), vec![0xc0, 0x0c, 0x24, 0x9f]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn tcp_transport_waits_for_the_echo() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut transport = TcpTransport::new(listener.local_addr().unwrap());
+    let bridge = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let byte = socket.read_u8().await.unwrap();
+        socket.write_u8(byte).await.unwrap();
+        let byte = socket.read_u8().await.unwrap();
+        socket.write_u8(!byte).await.unwrap();
+    });
+
+    transport.connect().await.unwrap();
+    transport.send(0x24).await.unwrap();
+    let err = transport.send(0x9f).await.unwrap_err();
+    transport.disconnect().await.unwrap();
+
+    assert_eq!(err.to_string(), "Expected reply is 9f but received 60");
+    bridge.await.unwrap();
+}
+
+#[tokio::test]
+async fn tcp_transport_stalls_without_the_echo() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut transport = TcpTransport::new(listener.local_addr().unwrap())
+        .with_timeout(Duration::from_millis(50))
+        .with_retries(1);
+    let bridge = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        // the first byte is confirmed after the first timeout, the second one never
+        let byte = socket.read_u8().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        socket.write_u8(byte).await.unwrap();
+        socket.read_u8().await.unwrap();
+        socket
+    });
+
+    transport.connect().await.unwrap();
+    transport.send(0x24).await.unwrap();
+    let err = transport.send(0x9f).await.unwrap_err();
+
+    let expected = Stalled {
+        byte: 0x9f,
+        waited: Duration::from_millis(100),
+    };
+    assert_eq!(err.downcast_ref::<Stalled>(), Some(&expected));
+    assert_eq!(transport.status(), Status::Stalled);
+    transport.disconnect().await.unwrap();
+    assert_eq!(transport.status(), Status::Stalled);
+    drop(bridge.await.unwrap());
+}
+
+#[tokio::test]
+async fn tcp_transport_gives_up_connecting() {
+    // nothing listens on the port once the listener is dropped
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let mut transport = TcpTransport::new(addr).with_connect_attempts(1);
+
+    let err = transport.connect().await.unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        format!("cannot connect to {addr} after 1 attempts")
+    );
+    assert_eq!(transport.status(), Status::Disconnected);
+}
+
+#[tokio::test]
+async fn sender_waits_for_the_hal_to_handle_the_instructions() {
+    let (tx, receiver) = unbounded_channel();
+    let (loopback, mut rx) = Loopback::new();
+    let mut hal = Hal::new(receiver, loopback);
+    let sender = SenderWrapper::new(tx).with_progress(hal.progress());
+
+    sender.send(Instruction::Idle(10)).await;
+    sender.send(Instruction::SendBytes(0x249f)).await;
+    let pending = tokio::time::timeout(Duration::from_millis(50), sender.wait_until_handled());
+    assert!(pending.await.is_err());
+
+    let handle = tokio::spawn(async move { hal.run().await });
+    sender.wait_until_handled().await;
+    assert_eq!(rx.try_recv(), Ok(0x24));
+    assert_eq!(rx.try_recv(), Ok(0x9f));
+
+    sender.send(Instruction::Halt).await;
+    handle.await.unwrap().unwrap();
+}
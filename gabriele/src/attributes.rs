@@ -0,0 +1,88 @@
+use crate::cmd::Impression;
+use crate::layout::Alignment;
+use crate::sign::Sign;
+use crate::symbol::AfterSymbolPrinted;
+
+/// The underscore on the standard daisy wheel
+pub const UNDERLINE: Sign = Sign {
+    idx: 51,
+    imp: Impression::Normal,
+    after: AfterSymbolPrinted::MoveRight,
+};
+
+/// The hyphen on the standard daisy wheel
+pub const STRIKETHROUGH: Sign = Sign {
+    idx: 3,
+    imp: Impression::Mild,
+    after: AfterSymbolPrinted::MoveRight,
+};
+
+/// Vertical placement of the printed characters against the line.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Script {
+    #[default]
+    Baseline,
+    Superscript,
+    Subscript,
+}
+
+impl Script {
+    /// Paper motion away from the baseline in typewriter units,
+    /// which is a half of the `line_height`.
+    pub fn shift(&self, line_height: i32) -> i32 {
+        match self {
+            Script::Baseline => 0,
+            Script::Superscript => -line_height / 2,
+            Script::Subscript => line_height / 2,
+        }
+    }
+}
+
+/// Text attributes applied to a run of printed characters,
+/// they are switched on and off on the Machine between the runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Attributes {
+    /// Every sign is struck twice with a slight offset
+    pub bold: bool,
+    /// Every character place including whitespace is overstruck with `_`
+    pub underline: bool,
+    /// Every character place including whitespace is overstruck with `-`
+    pub strikethrough: bool,
+    /// The characters are printed above or below the baseline
+    pub script: Script,
+}
+
+impl Attributes {
+    /// The signs overstruck on every character place of the run.
+    pub fn marks(&self) -> [Option<Sign>; 2] {
+        [
+            self.underline.then_some(UNDERLINE),
+            self.strikethrough.then_some(STRIKETHROUGH),
+        ]
+    }
+
+    pub fn is_overstruck(&self) -> bool {
+        self.underline || self.strikethrough
+    }
+}
+
+/// A piece of the printed input with its own text attributes, see `Machine::print_spans`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Span {
+    /// Length of the piece in bytes
+    pub len: usize,
+    pub attributes: Attributes,
+    /// Placement of the lines beginning in the piece, the Settings are followed when it is `None`
+    pub alignment: Option<Alignment>,
+}
+
+impl Span {
+    /// The span of the consecutive `spans` which covers the byte at `offset`.
+    pub fn find(spans: &[Span], offset: usize) -> Option<&Span> {
+        let mut end = 0;
+        spans.iter().find(|span| {
+            end += span.len;
+            offset < end
+        })
+    }
+}
@@ -1,12 +1,14 @@
+use crate::error::Error;
 use crate::printing::Instruction;
 use crate::symbol::CmdSymbol;
+use core::fmt;
 use deku::no_std_io::Cursor;
 use deku::prelude::Writer;
-use deku::{DekuWrite, DekuWriter};
+use deku::{DekuContainerRead, DekuRead, DekuWrite, DekuWriter};
 
 /// Top-level Command enum.
 /// The variant is identified by two most-significant bytes.
-#[derive(Debug, DekuWrite, PartialEq)]
+#[derive(Debug, DekuRead, DekuWrite, PartialEq)]
 #[deku(id_type = "u8", bits = 2)]
 #[deku(endian = "big")]
 pub enum Cmd {
@@ -21,25 +23,87 @@ pub enum Cmd {
 }
 
 impl Cmd {
+    /// Encodes the command into a 2-byte word.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a value does not fit into its bit field, see `try_as_u16`.
     pub fn as_u16(&self) -> u16 {
+        self.try_as_u16().expect("Failed to write bits")
+    }
+
+    /// Encodes the command into a 2-byte word,
+    /// fails when a value does not fit into its bit field.
+    pub fn try_as_u16(&self) -> Result<u16, Error> {
         let mut output = [0u8; 2];
 
         let mut cursor = Cursor::new(&mut output[..]);
         let mut deku_writer = Writer::new(&mut cursor);
 
         self.to_writer(&mut deku_writer, ())
-            .expect("Failed to write bits");
-        u16::from_be_bytes(output)
+            .map_err(|_| Error::CommandOverflow)?;
+        Ok(u16::from_be_bytes(output))
     }
 
     pub fn as_instruction(&self) -> Instruction {
         Instruction::SendBytes(self.as_u16())
     }
+
+    pub fn try_as_instruction(&self) -> Result<Instruction, Error> {
+        self.try_as_u16().map(Instruction::SendBytes)
+    }
+
+    /// Decodes a 2-byte word received or sent over the serial port.
+    pub fn from_u16(word: u16) -> Result<Self, InvalidCommand> {
+        let bytes = word.to_be_bytes();
+        match Self::from_bytes((&bytes, 0)) {
+            Ok((_, cmd)) => Ok(cmd),
+            Err(_) => Err(InvalidCommand(word)),
+        }
+    }
+
+    /// The petal index on the daisy wheel for the printing commands.
+    pub fn petal(&self) -> Option<u8> {
+        match self {
+            Cmd::SymbolLow(sym) => Some(sym.code),
+            Cmd::SymbolHigh(sym) => Some(sym.code | 0x40),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cmd::Motion(motion) => write!(f, "motion {} {}", motion.dir, motion.value),
+            Cmd::Jump(jump) => write!(f, "{jump}"),
+            Cmd::SymbolLow(sym) | Cmd::SymbolHigh(sym) => write!(
+                f,
+                "symbol {}, {}, {}",
+                self.petal().unwrap_or(sym.code),
+                sym.attr.impression,
+                sym.attr.direction
+            ),
+        }
+    }
+}
+
+/// The 2-byte word which cannot be decoded into a `Cmd`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InvalidCommand(pub u16);
+
+impl fmt::Display for InvalidCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid command {:#06x}", self.0)
+    }
 }
 
 /// Make a "jump" with the caret
 /// in a `Plus` or `Minus` direction.
-#[derive(Debug, DekuWrite, PartialEq)]
+///
+/// The session commands of the typewriter share the same 2-bit identifier,
+/// they switch it online and let it accept the printing commands.
+#[derive(Debug, DekuRead, DekuWrite, PartialEq)]
 #[deku(id_type = "u16", bits = 14)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
@@ -51,10 +115,39 @@ pub enum CmdJump {
     /// Caret motion from left to right, `=>`
     #[deku(id = 0b00_0011_0000_0000)]
     Plus,
+
+    /// The typewriter goes offline, the end of a session
+    #[deku(id = 0b10_0000_0000_0000)]
+    Offline,
+
+    /// The typewriter goes online, the beginning of a session
+    #[deku(id = 0b10_0001_0000_0000)]
+    Online,
+
+    /// The typewriter starts accepting the printing commands
+    #[deku(id = 0b10_0010_0000_0000)]
+    StartCommands,
+
+    /// The typewriter stops accepting the printing commands
+    #[deku(id = 0b10_0011_0000_0000)]
+    StopCommands,
+}
+
+impl fmt::Display for CmdJump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CmdJump::Minus => write!(f, "jump -x"),
+            CmdJump::Plus => write!(f, "jump +x"),
+            CmdJump::Offline => write!(f, "offline"),
+            CmdJump::Online => write!(f, "online"),
+            CmdJump::StartCommands => write!(f, "start commands"),
+            CmdJump::StopCommands => write!(f, "stop commands"),
+        }
+    }
 }
 
 /// Caret or paper motion direction.
-#[derive(Debug, DekuWrite, PartialEq)]
+#[derive(Debug, DekuRead, DekuWrite, PartialEq)]
 #[deku(id_type = "u8", bits = 2)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
@@ -76,13 +169,24 @@ pub enum CmdMotionDirection {
     MinusX,
 }
 
-#[derive(Debug, DekuWrite, PartialEq)]
+impl fmt::Display for CmdMotionDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CmdMotionDirection::PlusY => write!(f, "+y"),
+            CmdMotionDirection::MinusY => write!(f, "-y"),
+            CmdMotionDirection::PlusX => write!(f, "+x"),
+            CmdMotionDirection::MinusX => write!(f, "-x"),
+        }
+    }
+}
+
+#[derive(Debug, DekuRead, DekuWrite, PartialEq)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
 pub struct CmdMotion {
-    dir: CmdMotionDirection,
+    pub dir: CmdMotionDirection,
     #[deku(bits = 12)]
-    value: u16,
+    pub value: u16,
 }
 impl CmdMotion {
     /// Vertical scroll to the paper bottom (roll forward)
@@ -160,7 +264,7 @@ impl CmdMotion {
 /// The User has 4 pre-defined options and
 /// the custom impression value can be specified
 /// as a ratio between the base (0) and maximum (63).
-#[derive(Default, DekuWrite, Copy)]
+#[derive(Default, DekuRead, DekuWrite, Copy)]
 #[deku(id_type = "u8", bits = 6)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
@@ -183,39 +287,106 @@ pub enum Impression {
     Strongest,
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//     use crate::cmd::Cmd;
-//     use crate::symbol::{AfterSymbolPrinted, CmdSymbol, SymbolPrintingAttrs};
-//     use deku::DekuContainerWrite;
-//
-//     fn hit(impression: Impression) -> u8 {
-//         let mut sym = CmdSymbol::default();
-//         sym.attr = SymbolPrintingAttrs {
-//             direction: AfterSymbolPrinted::HoldOn,
-//             impression,
-//         };
-//         Cmd::SymbolLow(sym).to_bytes().unwrap()[1]
-//     }
-//
-//     #[test]
-//     fn mild() {
-//         assert_eq!(hit(Impression::Mild), 15)
-//     }
-//
-//     #[test]
-//     fn normal() {
-//         assert_eq!(hit(Impression::default()), 31)
-//     }
-//
-//     #[test]
-//     fn strong() {
-//         assert_eq!(hit(Impression::Strong), 47)
-//     }
-//
-//     #[test]
-//     fn strongest() {
-//         assert_eq!(hit(Impression::Strongest), 63)
-//     }
-// }
+impl fmt::Display for Impression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Impression::Mild => write!(f, "mild"),
+            Impression::Normal => write!(f, "normal"),
+            Impression::Strong => write!(f, "strong"),
+            Impression::Strongest => write!(f, "strongest"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::cmd::Cmd;
+    use crate::symbol::{AfterSymbolPrinted, CmdSymbol, SymbolPrintingAttrs};
+    use std::string::ToString;
+
+    fn hit(impression: Impression) -> u8 {
+        let sym = CmdSymbol {
+            attr: SymbolPrintingAttrs {
+                direction: AfterSymbolPrinted::HoldOn,
+                impression,
+            },
+            ..Default::default()
+        };
+        Cmd::SymbolLow(sym).as_u16().to_be_bytes()[1]
+    }
+
+    #[test]
+    fn mild() {
+        assert_eq!(hit(Impression::Mild), 15)
+    }
+
+    #[test]
+    fn normal() {
+        assert_eq!(hit(Impression::default()), 31)
+    }
+
+    #[test]
+    fn strong() {
+        assert_eq!(hit(Impression::Strong), 47)
+    }
+
+    #[test]
+    fn strongest() {
+        assert_eq!(hit(Impression::Strongest), 63)
+    }
+
+    #[test]
+    fn decodes_motion() {
+        let cmd = Cmd::from_u16(u16::from_be_bytes([0xe0, 120])).unwrap();
+        assert_eq!(cmd, Cmd::Motion(CmdMotion::minus_x(120)));
+        assert_eq!(cmd.to_string(), "motion -x 120");
+    }
+
+    #[test]
+    fn decodes_jump() {
+        let cmd = Cmd::from_u16(u16::from_be_bytes([0x83, 0])).unwrap();
+        assert_eq!(cmd, Cmd::Jump(CmdJump::Plus));
+        assert_eq!(cmd.to_string(), "jump +x");
+    }
+
+    #[test]
+    fn encodes_session() {
+        let words = [
+            CmdJump::Offline,
+            CmdJump::Online,
+            CmdJump::StartCommands,
+            CmdJump::StopCommands,
+        ]
+        .map(|cmd| Cmd::Jump(cmd).as_u16().to_be_bytes());
+        assert_eq!(words, [[0xa0, 0], [0xa1, 0], [0xa2, 0], [0xa3, 0]]);
+    }
+
+    #[test]
+    fn decodes_session() {
+        let cmd = Cmd::from_u16(u16::from_be_bytes([0xa2, 0])).unwrap();
+        assert_eq!(cmd, Cmd::Jump(CmdJump::StartCommands));
+        assert_eq!(cmd.to_string(), "start commands");
+    }
+
+    #[test]
+    fn decodes_high_symbol() {
+        let cmd = Cmd::from_u16(u16::from_be_bytes([93, 31])).unwrap();
+        assert_eq!(cmd.petal(), Some(93));
+        assert_eq!(cmd.to_string(), "symbol 93, normal, hold on");
+        assert_eq!(cmd.as_u16(), u16::from_be_bytes([93, 31]));
+    }
+
+    #[test]
+    fn rejects_motion_beyond_12_bits() {
+        let cmd = Cmd::Motion(CmdMotion::plus_y(0x1514));
+        assert_eq!(cmd.try_as_u16(), Err(Error::CommandOverflow));
+    }
+
+    #[test]
+    fn rejects_unknown_impression() {
+        let word = u16::from_be_bytes([36, 0x80 | 20]);
+        assert_eq!(Cmd::from_u16(word), Err(InvalidCommand(word)));
+    }
+}
@@ -1,20 +1,183 @@
 use crate::symbol::Symbol;
+use core::fmt;
 
 pub trait DaisyDatabase {
-    fn get(&self, character: char) -> &'static Symbol;
+    fn get(&self, character: char) -> Option<&'static Symbol>;
 }
 
 impl DaisyDatabase for &'static [Symbol] {
-    fn get(&self, character: char) -> &'static Symbol {
-        if let Some(sym) = self.iter().find(|symbol| symbol.character == character) {
-            return sym;
-        }
-        panic!("Symbol not found");
+    fn get(&self, character: char) -> Option<&'static Symbol> {
+        self.iter().find(|symbol| symbol.character == character)
     }
 }
 
 impl<T: DaisyDatabase + ?Sized> DaisyDatabase for &T {
-    fn get(&self, chr: char) -> &'static Symbol {
+    fn get(&self, chr: char) -> Option<&'static Symbol> {
         (*self).get(chr)
     }
 }
+
+/// Defines what happens with the characters which are missing on the daisy wheel.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum UnknownCharacterPolicy {
+    /// Nothing is printed when the input contains an unknown character
+    #[default]
+    Abort,
+
+    /// The unknown characters are left out
+    Skip,
+
+    /// The unknown characters are replaced with the given one, for example `?`
+    Substitute(char),
+
+    /// The unknown characters are replaced with similar looking ones, for example `–` with `-`;
+    /// nothing is printed when the input contains a character without a replacement
+    Transliterate,
+}
+
+/// The result of looking up a character on the daisy wheel
+/// with respect to `UnknownCharacterPolicy`.
+#[derive(Debug, PartialEq)]
+pub enum Lookup {
+    /// The Symbol to be printed for the character
+    Found(&'static Symbol),
+
+    /// The character should be left out
+    Skip,
+
+    /// The character cannot be printed
+    Unknown,
+}
+
+impl UnknownCharacterPolicy {
+    pub fn lookup(&self, db: &impl DaisyDatabase, character: char) -> Lookup {
+        if let Some(symbol) = db.get(character) {
+            return Lookup::Found(symbol);
+        }
+        let replacement = match self {
+            UnknownCharacterPolicy::Abort => None,
+            UnknownCharacterPolicy::Skip => return Lookup::Skip,
+            UnknownCharacterPolicy::Substitute(chr) => Some(*chr),
+            UnknownCharacterPolicy::Transliterate => transliterate(character),
+        };
+        match replacement.and_then(|chr| db.get(chr)) {
+            Some(symbol) => Lookup::Found(symbol),
+            None => Lookup::Unknown,
+        }
+    }
+}
+
+/// Finds a similar looking character for the ones
+/// which are often missing on a daisy wheel.
+pub fn transliterate(character: char) -> Option<char> {
+    let chr = match character {
+        '–' | '—' | '‐' | '‑' | '−' => '-',
+        '“' | '”' | '„' | '«' | '»' => '"',
+        '‘' | '‚' | '′' => '\'',
+        '\u{a0}' => ' ',
+        '×' => 'x',
+        '•' => '*',
+        '¹' => '1',
+        'â' | 'ã' | 'å' | 'ā' => 'a',
+        'Â' | 'Ã' | 'Å' | 'Ā' => 'A',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'ê' | 'ë' | 'ē' => 'e',
+        'Ê' | 'Ë' | 'Ē' => 'E',
+        'î' | 'ï' => 'i',
+        'Î' | 'Ï' => 'I',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ô' | 'õ' | 'ø' => 'o',
+        'Ô' | 'Õ' | 'Ø' => 'O',
+        'û' => 'u',
+        'Û' => 'U',
+        'ý' | 'ÿ' => 'y',
+        'Ý' => 'Y',
+        _ => return None,
+    };
+    Some(chr)
+}
+
+/// A character of the input which cannot be printed,
+/// `line` and `column` are counted from 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UnknownCharacter {
+    pub character: char,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for UnknownCharacter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown character {:?} at line {}, column {}",
+            self.character, self.line, self.column
+        )
+    }
+}
+
+/// The printing is aborted because of the unknown characters.
+/// `first` is the first one of the `count` unknown characters in the input.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UnknownCharacters {
+    pub first: UnknownCharacter,
+    pub count: usize,
+}
+
+impl fmt::Display for UnknownCharacters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.first)?;
+        if self.count > 1 {
+            write!(f, " and {} more", self.count - 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wheels::standard::SYMBOLS;
+
+    fn db() -> &'static [Symbol] {
+        &SYMBOLS
+    }
+
+    #[test]
+    fn test_missing_symbol() {
+        assert_eq!(DaisyDatabase::get(&db(), '€'), None);
+        assert_eq!(
+            UnknownCharacterPolicy::Abort.lookup(&db(), '€'),
+            Lookup::Unknown
+        );
+        assert_eq!(
+            UnknownCharacterPolicy::Skip.lookup(&db(), '€'),
+            Lookup::Skip
+        );
+    }
+
+    #[test]
+    fn test_substitute() {
+        let policy = UnknownCharacterPolicy::Substitute('?');
+        assert_eq!(
+            policy.lookup(&db(), '€'),
+            Lookup::Found(DaisyDatabase::get(&db(), '?').unwrap())
+        );
+        assert_eq!(
+            policy.lookup(&db(), 'a'),
+            Lookup::Found(DaisyDatabase::get(&db(), 'a').unwrap())
+        );
+    }
+
+    #[test]
+    fn test_transliterate() {
+        let policy = UnknownCharacterPolicy::Transliterate;
+        assert_eq!(
+            policy.lookup(&db(), '–'),
+            Lookup::Found(DaisyDatabase::get(&db(), '-').unwrap())
+        );
+        assert_eq!(policy.lookup(&db(), '€'), Lookup::Unknown);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::cmd::InvalidCommand;
+use crate::database::UnknownCharacters;
+use crate::motion::MotionOutOfRange;
+use core::fmt;
+
+/// Everything which prevents the typewriter from printing,
+/// the errors carry no allocated data to be usable on the microcontroller.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Error {
+    /// The input contains characters which cannot be printed
+    UnknownCharacters(UnknownCharacters),
+
+    /// The motion distance in typewriter units does not fit into `i16`
+    MotionOutOfRange(MotionOutOfRange),
+
+    /// The petal index is beyond the daisy wheel
+    InvalidPetal(u8),
+
+    /// A value of the command does not fit into its bit field
+    CommandOverflow,
+
+    /// The 2-byte word cannot be decoded into a command
+    InvalidCommand(InvalidCommand),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownCharacters(unknown) => write!(f, "{unknown}"),
+            Error::MotionOutOfRange(motion) => write!(f, "{motion}"),
+            Error::InvalidPetal(idx) => write!(f, "invalid petal index {idx}"),
+            Error::CommandOverflow => write!(f, "command value does not fit into its bits"),
+            Error::InvalidCommand(word) => write!(f, "{word}"),
+        }
+    }
+}
+
+impl From<UnknownCharacters> for Error {
+    fn from(unknown: UnknownCharacters) -> Self {
+        Error::UnknownCharacters(unknown)
+    }
+}
+
+impl From<MotionOutOfRange> for Error {
+    fn from(motion: MotionOutOfRange) -> Self {
+        Error::MotionOutOfRange(motion)
+    }
+}
+
+impl From<InvalidCommand> for Error {
+    fn from(word: InvalidCommand) -> Self {
+        Error::InvalidCommand(word)
+    }
+}
@@ -0,0 +1,166 @@
+/// Placement of the rows between the margins.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// The rows broken by the word wrap are stretched to the right margin,
+    /// the last row of every line stays ragged
+    Justify,
+}
+
+impl Alignment {
+    /// Distance from the left margin to the beginning of a row
+    /// when `space` typewriter units are left free in the row.
+    pub fn indent(&self, space: i32) -> i32 {
+        match self {
+            Alignment::Left => 0,
+            Alignment::Center => space.max(0) / 2,
+            Alignment::Right => space.max(0),
+            Alignment::Justify => 0,
+        }
+    }
+}
+
+/// Extra space in typewriter units spread over the gaps between the words of a row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Justification {
+    pub extra: i32,
+    pub gaps: i32,
+}
+
+impl Justification {
+    /// Spreads the `extra` space over the gaps of the `row`,
+    /// nothing is spread when the row has no gaps or no space is left.
+    pub fn new(row: &str, extra: i32) -> Option<Self> {
+        let words = row.split(' ').filter(|word| !word.is_empty()).count() as i32;
+        let gaps = words - 1;
+        (gaps > 0 && extra > 0).then_some(Self { extra, gaps })
+    }
+
+    /// Extra space for the gap at `idx` counted from the left,
+    /// the remainder goes to the leftmost gaps one unit each.
+    pub fn gap(&self, idx: i32) -> i32 {
+        self.extra / self.gaps + i32::from(idx < self.extra % self.gaps)
+    }
+}
+
+/// Splits off the first row of a line of text which fits between the margins.
+/// Returns the row and the rest of the text to be printed in the following rows.
+///
+/// - `start` is the carriage position where the row begins;
+/// - `left` and `right` are the margins, the following rows begin at `left`;
+/// - `advance` gives the carriage travel in typewriter units for a character
+///   handled at the carriage position.
+///
+/// The row is broken at the last whitespace which fits, the whitespace itself is dropped.
+/// A word longer than the whole row is broken at the right margin.
+pub fn wrap(
+    text: &str,
+    start: i32,
+    left: i32,
+    right: i32,
+    advance: impl Fn(char, i32) -> i32,
+) -> (&str, &str) {
+    let mut x = start;
+    let mut last_break: Option<(usize, usize)> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((idx, chr)) = chars.next() {
+        if chr == ' ' {
+            x += advance(chr, x);
+            while let Some((_, space)) = chars.next_if(|(_, next)| *next == ' ') {
+                x += advance(space, x);
+            }
+            let Some((next_start, _)) = chars.peek().copied() else {
+                // the trailing whitespace may not reach the right margin
+                return if x > right {
+                    (&text[..idx], "")
+                } else {
+                    (text, "")
+                };
+            };
+            last_break = Some((idx, next_start));
+            continue;
+        }
+
+        let width = advance(chr, x);
+        if x + width <= right {
+            x += width;
+            continue;
+        }
+
+        return match last_break {
+            Some((end, next_start)) if end > 0 => (&text[..end], &text[next_start..]),
+            // the leading whitespace ends the row begun by the previous text
+            Some((_, next_start)) if start > left => ("", &text[next_start..]),
+            // the carriage should return before the word can be started
+            _ if idx == 0 && start > left => ("", text),
+            // at least one character is printed in each row
+            _ if idx == 0 => text.split_at(chr.len_utf8()),
+            _ => text.split_at(idx),
+        };
+    }
+    (text, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wrap, Alignment, Justification};
+
+    fn fixed(_: char, _: i32) -> i32 {
+        12
+    }
+
+    #[test]
+    fn test_short_line_is_kept() {
+        assert_eq!(wrap("ab cd", 0, 0, 60, fixed), ("ab cd", ""));
+    }
+
+    #[test]
+    fn test_wraps_at_whitespace() {
+        assert_eq!(wrap("ab cd  ef", 0, 0, 72, fixed), ("ab cd", "ef"));
+        assert_eq!(wrap("ef", 0, 0, 72, fixed), ("ef", ""));
+    }
+
+    #[test]
+    fn test_breaks_long_words() {
+        assert_eq!(wrap("abcdefgh", 0, 0, 36, fixed), ("abc", "defgh"));
+    }
+
+    #[test]
+    fn test_continues_after_the_current_position() {
+        assert_eq!(wrap("abc", 48, 0, 48, fixed), ("", "abc"));
+        assert_eq!(wrap("ab cd", 12, 0, 48, fixed), ("ab", "cd"));
+        assert_eq!(wrap(" defgh", 36, 0, 60, fixed), ("", "defgh"));
+    }
+
+    #[test]
+    fn test_drops_overflowing_whitespace() {
+        assert_eq!(wrap("abc   ", 0, 0, 36, fixed), ("abc", ""));
+    }
+
+    #[test]
+    fn test_centers_in_the_free_space() {
+        assert_eq!(Alignment::Left.indent(60), 0);
+        assert_eq!(Alignment::Center.indent(60), 30);
+        assert_eq!(Alignment::Center.indent(-12), 0);
+        assert_eq!(Alignment::Right.indent(60), 60);
+    }
+
+    #[test]
+    fn test_spreads_the_space_over_the_gaps() {
+        let justification = Justification::new("  ab cd  ef", 8).unwrap();
+        assert_eq!(justification.gaps, 2);
+        assert_eq!(justification.gap(0), 4);
+        assert_eq!(justification.gap(1), 4);
+
+        let justification = Justification::new("a b c d", 5).unwrap();
+        let gaps = [0, 1, 2].map(|idx| justification.gap(idx));
+        assert_eq!(gaps, [2, 2, 1]);
+
+        assert_eq!(Justification::new("abc", 8), None);
+        assert_eq!(Justification::new("a b", 0), None);
+    }
+}
@@ -1,12 +1,20 @@
 #![no_std]
+pub mod attributes;
 pub mod cmd;
 pub mod database;
+pub mod error;
+pub mod layout;
 pub mod machine;
 pub mod motion;
+pub mod page;
+pub mod pause;
 pub mod position;
 pub mod printing;
 pub mod resolution;
 pub mod sign;
 pub mod symbol;
 pub mod to_symbols;
+pub mod validation;
 pub mod wheels;
+
+pub use error::Error;
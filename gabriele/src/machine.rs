@@ -1,29 +1,99 @@
-use crate::database::DaisyDatabase;
+use crate::attributes::{Attributes, Span};
+use crate::cmd::{Cmd, CmdJump};
+use crate::database::{
+    DaisyDatabase, Lookup, UnknownCharacter, UnknownCharacterPolicy, UnknownCharacters,
+};
+use crate::error::Error;
+use crate::layout::{self, Alignment, Justification};
 use crate::motion::move_relative;
+use crate::page::Page;
+use crate::pause::Pauses;
 use crate::position::Position;
 use crate::printing::{Action, Instruction};
+use crate::resolution::{Resolution, DEFAULT_X_RESOLUTION};
+use crate::symbol::ActionMapping;
 use crate::to_symbols::ToSymbols;
+use crate::validation::{InstructionCounter, Overrun, Report};
 use core::default::Default;
+use either::Either;
 use itertools::Itertools;
 
 pub trait InstructionSender {
     #[allow(async_fn_in_trait)]
     async fn send(&self, instr: Instruction);
+
+    /// Called after the full sheet is ejected, returns when
+    /// the operator has inserted a new one with its upper edge at the printing point.
+    #[allow(async_fn_in_trait)]
+    async fn change_paper(&self) {}
 }
 
-pub struct Machine<T: InstructionSender, D: DaisyDatabase + 'static> {
+pub struct Machine<T: InstructionSender, D: DaisyDatabase> {
     sender: T,
     position: Position,
     settings: Settings,
     db: D,
+    /// The current row is printed from the right to the left
+    reversed: bool,
+    /// Nothing is printed since the last line feed, the next row is placed between the margins
+    line_start: bool,
+    attributes: Attributes,
 }
 
-#[derive(Default, Copy, Clone, Debug)]
+/// The carriage travel of Gabriele 9009 in typewriter units,
+/// which is 130 characters with the default resolution.
+pub const DEFAULT_CARRIAGE_TRAVEL: i32 = 1560;
+
+#[derive(Copy, Clone, Debug)]
 pub struct Settings {
     pub direction: PrintingDirection,
     pub base_position: Position,
+    /// Print every other line from the right to the left,
+    /// saving the carriage return between the lines.
+    pub bidirectional: bool,
+    /// What happens with the characters missing on the daisy wheel
+    pub unknown_characters: UnknownCharacterPolicy,
+    /// The rightmost carriage position in typewriter units
+    pub carriage_travel: i32,
+    /// The carriage position in typewriter units where every line begins
+    pub left_margin: i32,
+    /// The carriage position in typewriter units which no line goes beyond
+    pub right_margin: i32,
+    /// Break the lines longer than the space between the margins
+    pub word_wrap: bool,
+    /// Shift of the second strike of the bold characters in typewriter units
+    pub bold_offset: i32,
+    /// Placement of the rows between the margins
+    pub alignment: Alignment,
+    /// Distance between the tab stops in typewriter units, counted from the left margin,
+    /// the non-positive distances are taken as one unit
+    pub tab_stops: i32,
+    /// The paper sheet to break the pages at, the paper is endless when it is `None`
+    pub page: Option<Page>,
+    /// The pauses made between the commands
+    pub pauses: Pauses,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            direction: Default::default(),
+            base_position: Default::default(),
+            bidirectional: false,
+            unknown_characters: Default::default(),
+            carriage_travel: DEFAULT_CARRIAGE_TRAVEL,
+            left_margin: 0,
+            right_margin: DEFAULT_CARRIAGE_TRAVEL,
+            word_wrap: true,
+            bold_offset: 1,
+            alignment: Default::default(),
+            tab_stops: 8 * DEFAULT_X_RESOLUTION,
+            page: None,
+            pauses: Default::default(),
+        }
+    }
 }
-#[derive(Default, Copy, Clone, Debug)]
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
 pub enum PrintingDirection {
     #[default]
     Right,
@@ -39,7 +109,7 @@ impl From<PrintingDirection> for i32 {
     }
 }
 
-impl<T: InstructionSender, D: DaisyDatabase + 'static> Machine<T, D> {
+impl<T: InstructionSender, D: DaisyDatabase> Machine<T, D> {
     pub fn new(sender: T, db: D) -> Self {
         let position = Default::default();
         let settings = Default::default();
@@ -48,6 +118,9 @@ impl<T: InstructionSender, D: DaisyDatabase + 'static> Machine<T, D> {
             position,
             settings,
             db,
+            reversed: false,
+            line_start: true,
+            attributes: Default::default(),
         }
     }
 
@@ -55,30 +128,532 @@ impl<T: InstructionSender, D: DaisyDatabase + 'static> Machine<T, D> {
         self.position
     }
 
+    /// Changes the pitch and the line spacing of everything printed from now on.
+    pub fn set_resolution(&mut self, res: Resolution) {
+        self.position.res = res;
+    }
+
+    pub fn sender(&self) -> &T {
+        &self.sender
+    }
+
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// The text attributes applied to everything printed from now on.
+    pub fn attributes_mut(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
+
+    /// Switches the typewriter online. The bridge firmware does it on its own,
+    /// a host talking straight to the typewriter's UART calls it before `start`.
+    pub async fn connect(&mut self) {
+        self.transmit([Cmd::Jump(CmdJump::Online).as_instruction()].into_iter())
+            .await;
+    }
+
+    /// Lets the typewriter accept the printing commands.
+    pub async fn start(&mut self) {
+        self.transmit([Cmd::Jump(CmdJump::StartCommands).as_instruction()].into_iter())
+            .await;
+    }
+
+    /// Stops the typewriter accepting the printing commands and switches it offline.
+    pub async fn stop(&mut self) {
+        let session = [CmdJump::StopCommands, CmdJump::Offline];
+        self.transmit(
+            session
+                .map(|cmd| Cmd::Jump(cmd).as_instruction())
+                .into_iter(),
+        )
+        .await;
+    }
+
     pub async fn shutdown(&mut self) {
         self.transmit([Instruction::Halt].into_iter()).await;
     }
 
+    /// Sends the `instructions`, each one is followed by its pause, see `Pauses::after`.
     pub async fn transmit(&self, instructions: impl Iterator<Item = Instruction>) {
         for item in instructions {
             self.sender.send(item).await;
+            self.idle(self.settings.pauses.after(&item)).await;
         }
     }
 
-    pub async fn print(&mut self, input: &str) {
-        let symbols = input
-            .to_symbols(&self.db)
-            .dedup_by_with_count(|x, y| x == y && x.is_groupable());
+    /// Makes the typewriter wait for `ms` milliseconds.
+    pub async fn idle(&self, ms: u32) {
+        if ms > 0 {
+            self.sender.send(Instruction::Idle(ms)).await;
+        }
+    }
 
-        for (rep, symbol) in symbols {
-            let action = Action::new(symbol, &self.settings, rep, &self.position);
+    /// Lists all the characters of the `input` which cannot be printed
+    /// with respect to `UnknownCharacterPolicy` in the Settings.
+    pub fn unknown_characters<'a>(
+        &'a self,
+        input: &'a str,
+    ) -> impl Iterator<Item = UnknownCharacter> + 'a {
+        input.unknown_characters(&self.db, self.settings.unknown_characters)
+    }
+
+    fn check(&self, input: &str) -> Result<(), Error> {
+        let mut unknown = self.unknown_characters(input);
+        if let Some(first) = unknown.next() {
+            return Err(Error::UnknownCharacters(UnknownCharacters {
+                first,
+                count: 1 + unknown.count(),
+            }));
+        }
+        let policy = self.settings.unknown_characters;
+        input
+            .chars()
+            .try_for_each(|chr| match policy.lookup(&self.db, chr) {
+                Lookup::Found(symbol) => symbol.check(),
+                _ => Ok(()),
+            })
+    }
+
+    /// Lists all the lines of the `input` which do not fit into the carriage travel.
+    pub fn overruns<'a>(&'a self, input: &'a str) -> impl Iterator<Item = Overrun> + 'a {
+        input.lines().enumerate().filter_map(move |(idx, text)| {
+            let mut x = if idx == 0 && !self.line_start {
+                self.position.x
+            } else {
+                self.settings.left_margin
+            };
+            let mut end = x;
+            let mut rest = text;
+            loop {
+                let (row, next) = self.wrap(rest, x);
+                end = end.max(self.text_extent(row, x));
+                if next.is_empty() {
+                    break;
+                }
+                rest = next;
+                x = self.settings.left_margin;
+            }
+            (end > self.settings.carriage_travel).then_some(Overrun { line: idx + 1, end })
+        })
+    }
+
+    /// Makes a dry run over the `input` without sending anything to the typewriter,
+    /// the sheets of paper are counted when the Settings have the page geometry.
+    pub async fn validate(&self, input: &str) -> Report {
+        self.validate_spans(input, &[]).await
+    }
+
+    /// Makes a dry run over the `input` split into the consecutive `spans`,
+    /// as they are printed by `print_spans`.
+    pub async fn validate_spans(&self, input: &str, spans: &[Span]) -> Report {
+        let mut dry_run = Machine {
+            sender: InstructionCounter::default(),
+            position: self.position,
+            settings: self.settings,
+            db: &self.db,
+            reversed: self.reversed,
+            line_start: self.line_start,
+            attributes: self.attributes,
+        };
+        // the dry run stops at the first error, the instructions up to it are counted
+        let error = dry_run.print_checked(input, spans).await.err();
+        let sheets = match self.settings.page {
+            Some(_) => 1 + dry_run.sender.paper_changes(),
+            None => 0,
+        };
+
+        Report {
+            unknown_characters: self.unknown_characters(input).count(),
+            overruns: self.overruns(input).count(),
+            instructions: dry_run.sender.count(),
+            sheets,
+            error,
+        }
+    }
+
+    /// Prints the `input`, nothing is sent to the typewriter
+    /// when the input contains characters which cannot be printed.
+    pub async fn print(&mut self, input: &str) -> Result<(), Error> {
+        self.print_spans(input, &[]).await
+    }
+
+    /// Prints the `input` split into the consecutive `spans`, each one with its own
+    /// text attributes and alignment. The lines are laid out as a whole across the spans,
+    /// the input beyond the spans is printed with the attributes of the Machine.
+    /// Nothing is sent to the typewriter when the input contains characters
+    /// which cannot be printed.
+    pub async fn print_spans(&mut self, input: &str, spans: &[Span]) -> Result<(), Error> {
+        self.check(input)?;
+        self.print_checked(input, spans).await
+    }
+
+    /// Prints the input line by line, each line is split into rows
+    /// which fit between the margins.
+    async fn print_checked(&mut self, input: &str, spans: &[Span]) -> Result<(), Error> {
+        let mut lines = input.split_inclusive(['\n', '\x0c']).peekable();
+        let mut offset = 0;
+
+        while let Some(line) = lines.next() {
+            let (mut text, terminator) = split_line(line);
+            let alignment = self.alignment_at(spans, offset);
+            let mut row_offset = offset;
+            offset += line.len();
+
+            let row = loop {
+                // the reversed rows are laid out from the left margin as well
+                let x = if self.reversed || self.line_start {
+                    self.settings.left_margin
+                } else {
+                    self.position.x
+                };
+                let (row, rest) = self.wrap(text, x);
+                if !is_blank(row) {
+                    self.page_break().await;
+                }
+                if !self.reversed && self.line_start {
+                    let mut target = self.position;
+                    target.x = self.row_start(row, alignment);
+                    self.send_motion(&target).await;
+                    self.position = target;
+                }
+                let justification = self.justification(row, !rest.is_empty(), alignment);
+                self.print_row(row, row_offset, spans, justification)
+                    .await?;
+                self.line_start &= row.is_empty();
+                if rest.is_empty() {
+                    break row;
+                }
+                let next = (rest, terminator.is_some(), alignment);
+                self.line_feed(1, row, Some(next)).await;
+                row_offset += text.len() - rest.len();
+                text = rest;
+            };
+
+            match terminator {
+                Some('\n') => {
+                    let mut feeds = 1;
+                    while let Some(blank) =
+                        lines.next_if(|next| split_line(next) == ("", Some('\n')))
+                    {
+                        feeds += 1;
+                        offset += blank.len();
+                    }
+                    let next = lines.peek().map(|next| {
+                        let (text, terminator) = split_line(next);
+                        (text, terminator.is_some(), self.alignment_at(spans, offset))
+                    });
+                    self.line_feed(feeds, row, next).await;
+                }
+                Some(_) => {
+                    self.reversed = false;
+                    let form_feed = &line[text.len()..];
+                    self.print_row(form_feed, offset - form_feed.len(), spans, None)
+                        .await?;
+                    self.line_start = true;
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the paper by the number of `feeds` and the carriage to the left margin.
+    ///
+    /// In the bidirectional mode every other row is printed from the right to the left,
+    /// then the carriage is moved straight to the last character of the `next` row
+    /// instead of the carriage return. `next` is the text to be printed after the line feed
+    /// with a flag whether the line is complete and its alignment, the rows of incomplete lines
+    /// are always printed from the left to the right.
+    async fn line_feed(&mut self, feeds: i32, row: &str, next: Option<(&str, bool, Alignment)>) {
+        let left_margin = self.settings.left_margin;
+        let mut target = self.position;
+        target.apply_line_feed(feeds);
+        target.x = left_margin;
+
+        let next_row = next.map(|(text, terminated, alignment)| {
+            let (next_row, rest) = self.wrap(text, left_margin);
+            let justification = self.justification(next_row, !rest.is_empty(), alignment);
+            (
+                next_row,
+                terminated || !rest.is_empty(),
+                alignment,
+                justification,
+            )
+        });
+        self.reversed = match next_row {
+            Some((next_row, true, alignment, justification))
+                if self.settings.bidirectional
+                    && !self.reversed
+                    && !is_blank(row)
+                    && !is_blank(next_row)
+                    && self.is_reversible(next_row) =>
+            {
+                let start = self.row_start(next_row, alignment);
+                let end = match justification {
+                    Some(_) => self.settings.right_margin,
+                    None => self.text_end(next_row.trim_end_matches(' '), start),
+                };
+                target.x = end - target.res.x;
+                true
+            }
+            _ => false,
+        };
+
+        self.send_motion(&target).await;
+
+        self.position = target;
+        self.line_start = true;
+    }
+
+    /// Moves the carriage and the paper from the current position to the `target`,
+    /// the distances which do not fit into a single `move_relative` are made in several parts.
+    async fn send_motion(&self, target: &Position) {
+        let (mut dx, mut dy) = target.diff(&self.position);
+        while dx != 0 || dy != 0 {
+            let x = i16::try_from(dx).unwrap_or(if dx < 0 { i16::MIN } else { i16::MAX });
+            let y = i16::try_from(dy).unwrap_or(if dy < 0 { i16::MIN } else { i16::MAX });
+            self.transmit(move_relative(x, y)).await;
+            (dx, dy) = (dx - i32::from(x), dy - i32::from(y));
+        }
+    }
+
+    /// Makes sure the next row is printed between the top and bottom margins of the page.
+    /// When the page is full, the sheet is ejected and the printing continues
+    /// on a new sheet at the top margin once the operator has changed the paper.
+    async fn page_break(&mut self) {
+        let Some(page) = self.settings.page else {
+            return;
+        };
+        if page.is_full(&self.position) {
+            let mut target = self.position;
+            target.y += page.eject(&self.position);
+            self.send_motion(&target).await;
+            self.position = target;
+            self.sender.change_paper().await;
+            self.idle(self.settings.pauses.page).await;
+            self.position.y = 0;
+        }
+        if self.position.y < page.top_margin {
+            let mut target = self.position;
+            target.y = page.top_margin;
+            self.send_motion(&target).await;
+            self.position = target;
+        }
+    }
+
+    /// Moves the paper by `dy` typewriter units without moving the carriage.
+    async fn roll(&mut self, dy: i32) {
+        let mut target = self.position;
+        target.y += dy;
+        self.send_motion(&target).await;
+        self.position = target;
+    }
+
+    /// Prints a single row without the line feed, `offset` is the position of the row
+    /// in the input split into the `spans`.
+    /// The reversed rows are printed from the last character to the first one,
+    /// starting at the current position which is expected to be at the last one.
+    async fn print_row(
+        &mut self,
+        row: &str,
+        offset: usize,
+        spans: &[Span],
+        justification: Option<Justification>,
+    ) -> Result<(), Error> {
+        let reversed = self.reversed;
+        let (direction, chars) = if reversed {
+            let chars = row.trim_end_matches(' ').char_indices().rev();
+            (PrintingDirection::Left, Either::Right(chars))
+        } else {
+            (self.settings.direction, Either::Left(row.char_indices()))
+        };
+        let settings = Settings {
+            direction,
+            ..self.settings
+        };
+        let policy = self.settings.unknown_characters;
+        let defaults = self.attributes;
+        let symbols = chars
+            .filter_map(|(idx, chr)| match policy.lookup(&self.db, chr) {
+                Lookup::Found(symbol) => {
+                    let span = Span::find(spans, offset + idx);
+                    Some((symbol, span.map_or(defaults, |span| span.attributes)))
+                }
+                _ => None,
+            })
+            .dedup_by_with_count(|x, y| x == y && x.0.is_groupable());
+
+        // the gaps between the words are counted from the left in both directions
+        let mut printed = false;
+        let mut gaps = 0;
+        // the paper is rolled off the baseline whenever the script changes along the row
+        let mut shift = 0;
+        for (rep, (symbol, attributes)) in symbols {
+            let script = attributes.script.shift(self.position.res.y);
+            let mut target = self.position;
+            target.y += script - shift;
+            self.send_motion(&target).await;
+            self.position = target;
+            shift = script;
+
+            let action =
+                Action::new(symbol, &settings, rep, &self.position).with_attributes(attributes);
             let target_pos = action.target_position();
 
-            for instr in action.instructions(&target_pos) {
-                self.sender.send(instr).await;
+            let extra = match justification {
+                Some(justification)
+                    if symbol.act == ActionMapping::Whitespace
+                        && (printed || reversed)
+                        && gaps < justification.gaps =>
+                {
+                    let idx = if reversed {
+                        justification.gaps - 1 - gaps
+                    } else {
+                        gaps
+                    };
+                    gaps += 1;
+                    justification.gap(idx) * i32::from(direction)
+                }
+                _ => 0,
+            };
+            printed |= symbol.act == ActionMapping::Print;
+
+            if extra == 0 || attributes.is_overstruck() {
+                let sent = match action.instructions(&target_pos) {
+                    Ok(instructions) => {
+                        self.transmit(instructions).await;
+                        true
+                    }
+                    // only the bare motions are made in parts, the strikes cannot be split
+                    Err(Error::MotionOutOfRange(_))
+                        if symbol.act != ActionMapping::Print && !attributes.is_overstruck() =>
+                    {
+                        false
+                    }
+                    Err(err) => return Err(err),
+                };
+                // the motion out of range of a single move is made in parts
+                if !sent {
+                    self.send_motion(&target_pos).await;
+                }
+                self.position = target_pos;
             }
-            self.position = target_pos;
+            let mut end = target_pos;
+            end.x += extra;
+            if extra != 0 && attributes.is_overstruck() {
+                // the marks cover the stretched gap as well, each one is struck
+                // a character place back from the end of its step
+                let mark = Action::new(symbol, &settings, 1, &self.position);
+                let place = (mark.target_position().x - self.position.x).abs();
+                let mut left = extra.abs();
+                while left > 0 && place > 0 {
+                    let step = left.min(place);
+                    let mut target = self.position;
+                    target.x -= (place - step) * i32::from(direction);
+                    self.send_motion(&target).await;
+                    self.position = target;
+
+                    let mark = Action::new(symbol, &settings, 1, &self.position)
+                        .with_attributes(attributes);
+                    let target = mark.target_position();
+                    self.transmit(mark.instructions(&target)?).await;
+                    self.position = target;
+                    left -= step;
+                }
+            }
+            // otherwise the stretched gap is a single carriage motion
+            self.send_motion(&end).await;
+            self.position = end;
+        }
+        self.roll(-shift).await;
+        Ok(())
+    }
+
+    /// The extra space for the row which is stretched to the right margin,
+    /// only the rows followed by a `soft_break` of the word wrap are justified.
+    fn justification(
+        &self,
+        row: &str,
+        soft_break: bool,
+        alignment: Alignment,
+    ) -> Option<Justification> {
+        if alignment != Alignment::Justify || !soft_break {
+            return None;
+        }
+        let end = self.text_end(row.trim_end_matches(' '), self.settings.left_margin);
+        Justification::new(row, self.settings.right_margin - end)
+    }
+
+    /// Splits off the first row of the `text` starting at `x`, see `layout::wrap`.
+    fn wrap<'a>(&self, text: &'a str, x: i32) -> (&'a str, &'a str) {
+        if !self.settings.word_wrap {
+            return (text, "");
         }
+        let left = self.settings.left_margin;
+        let right = self.settings.right_margin;
+        layout::wrap(text, x, left, right, |chr, x| self.advance(chr, x))
+    }
+
+    /// Measures the carriage travel in typewriter units
+    /// for the character handled at the carriage position `x`.
+    fn advance(&self, chr: char, x: i32) -> i32 {
+        let Lookup::Found(symbol) = self.settings.unknown_characters.lookup(&self.db, chr) else {
+            return 0;
+        };
+        let position = Position { x, ..self.position };
+        let settings = Settings {
+            direction: PrintingDirection::Right,
+            ..self.settings
+        };
+        Action::new(symbol, &settings, 1, &position)
+            .target_position()
+            .x
+            - x
+    }
+
+    /// The carriage position where the row beginning a line is printed
+    /// with respect to the `alignment`.
+    fn row_start(&self, row: &str, alignment: Alignment) -> i32 {
+        let left = self.settings.left_margin;
+        let end = self.text_end(row.trim_end_matches(' '), left);
+        let space = self.settings.right_margin - end;
+        left + alignment.indent(space)
+    }
+
+    /// The alignment of the line beginning at `offset` of the input, see `print_spans`.
+    fn alignment_at(&self, spans: &[Span], offset: usize) -> Alignment {
+        Span::find(spans, offset)
+            .and_then(|span| span.alignment)
+            .unwrap_or(self.settings.alignment)
+    }
+
+    /// The carriage position after the text is printed starting at `x`.
+    fn text_end(&self, text: &str, x: i32) -> i32 {
+        text.chars().fold(x, |x, chr| x + self.advance(chr, x))
+    }
+
+    /// The rightmost carriage position reached while the text is printed starting at `x`.
+    fn text_extent(&self, text: &str, x: i32) -> i32 {
+        text.chars()
+            .scan(x, |x, chr| {
+                *x += self.advance(chr, *x);
+                Some(*x)
+            })
+            .fold(x, i32::max)
+    }
+
+    /// Only the rows of fixed pitch printable characters and whitespace can be printed backwards,
+    /// the other motions depend on the carriage position or on the neighbouring characters.
+    fn is_reversible(&self, row: &str) -> bool {
+        let policy = self.settings.unknown_characters;
+        row.chars().all(|chr| match policy.lookup(&self.db, chr) {
+            Lookup::Found(symbol) => {
+                matches!(symbol.act, ActionMapping::Print | ActionMapping::Whitespace)
+                    && symbol.width.is_none()
+            }
+            _ => true,
+        })
     }
 
     pub async fn offset(&mut self, value: i16) {
@@ -89,3 +664,18 @@ impl<T: InstructionSender, D: DaisyDatabase + 'static> Machine<T, D> {
         self.transmit([].into_iter()).await;
     }
 }
+
+/// Splits the line terminator off, a carriage return before the line feed is dropped.
+fn split_line(line: &str) -> (&str, Option<char>) {
+    if let Some(text) = line.strip_suffix('\n') {
+        (text.strip_suffix('\r').unwrap_or(text), Some('\n'))
+    } else if let Some(text) = line.strip_suffix('\x0c') {
+        (text, Some('\x0c'))
+    } else {
+        (line, None)
+    }
+}
+
+fn is_blank(text: &str) -> bool {
+    text.chars().all(|chr| chr == ' ')
+}
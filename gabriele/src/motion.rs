@@ -1,30 +1,66 @@
 use crate::cmd::{Cmd, CmdJump, CmdMotion};
 use crate::position::Position;
 use crate::printing::Instruction;
+use core::fmt;
+
+/// The longest distance in typewriter units made by a single motion command,
+/// its value is a 12-bit field.
+pub const MAX_MOTION: u16 = 0xfff;
+
+/// The distance in typewriter units which does not fit into `i16`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MotionOutOfRange(pub i32);
+
+impl fmt::Display for MotionOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "motion of {} units is out of range", self.0)
+    }
+}
+
+/// Splits the `increment` into the motion commands of at most `MAX_MOTION` units each.
+fn motions(
+    increment: i16,
+    delta: fn(i16) -> Option<CmdMotion>,
+) -> impl Iterator<Item = Instruction> {
+    let sign = increment.signum();
+    let mut rest = increment.unsigned_abs();
+    core::iter::from_fn(move || {
+        let step = rest.min(MAX_MOTION);
+        rest -= step;
+        delta(step as i16 * sign)
+    })
+    .map(Cmd::Motion)
+    .map(|cmd| cmd.as_instruction())
+}
 
 fn move_carriage(increment: i16) -> impl Iterator<Item = Instruction> {
-    [CmdMotion::delta_x(increment)]
-        .into_iter()
-        .flatten()
-        .map(Cmd::Motion)
-        .map(|cmd| cmd.as_instruction())
+    motions(increment, CmdMotion::delta_x)
 }
 
 fn move_paper(increment: i16) -> impl Iterator<Item = Instruction> {
-    [CmdMotion::delta_y(increment)]
-        .into_iter()
-        .flatten()
-        .map(Cmd::Motion)
-        .map(|cmd| cmd.as_instruction())
+    motions(increment, CmdMotion::delta_y)
 }
 
 pub fn move_relative(x: i16, y: i16) -> impl Iterator<Item = Instruction> {
     move_carriage(x).chain(move_paper(y))
 }
 
-pub fn move_absolute(actual: &Position, target: &Position) -> impl Iterator<Item = Instruction> {
+/// Same as `move_relative` for the distances which may not fit into `i16`.
+pub fn try_move_relative(
+    x: i32,
+    y: i32,
+) -> Result<impl Iterator<Item = Instruction>, MotionOutOfRange> {
+    let x = i16::try_from(x).map_err(|_| MotionOutOfRange(x))?;
+    let y = i16::try_from(y).map_err(|_| MotionOutOfRange(y))?;
+    Ok(move_relative(x, y))
+}
+
+pub fn move_absolute(
+    actual: &Position,
+    target: &Position,
+) -> Result<impl Iterator<Item = Instruction>, MotionOutOfRange> {
     let (x, y) = target.diff(actual);
-    move_relative(x as i16, y as i16)
+    try_move_relative(x, y)
 }
 
 pub fn space_jump_left() -> impl Iterator<Item = Instruction> {
@@ -72,7 +108,7 @@ mod tests {
 
     #[test]
     fn it_moves_the_carriage_one_character_place_rightwards() {
-        let mut cmd = move_carriage(1 * X_RES as i16);
+        let mut cmd = move_carriage(X_RES as i16);
 
         let det = u16::from_be_bytes([0xc0, 12]);
 
@@ -82,7 +118,7 @@ mod tests {
 
     #[test]
     fn it_moves_the_carriage_one_character_place_leftwards() {
-        let mut cmd = move_carriage(-1 * X_RES as i16);
+        let mut cmd = move_carriage(-(X_RES as i16));
 
         let det = u16::from_be_bytes([0xe0, 12]);
 
@@ -92,7 +128,7 @@ mod tests {
 
     #[test]
     fn it_rolls_the_paper_one_line_downwards() {
-        let mut cmd = move_paper(1 * Y_RES as i16);
+        let mut cmd = move_paper(Y_RES as i16);
 
         let det = u16::from_be_bytes([0xd0, 16]);
 
@@ -102,7 +138,7 @@ mod tests {
 
     #[test]
     fn it_rolls_the_paper_one_line_upwards() {
-        let mut cmd = move_paper(-1 * Y_RES as i16);
+        let mut cmd = move_paper(-(Y_RES as i16));
 
         let det = u16::from_be_bytes([0xf0, 16]);
 
@@ -110,6 +146,31 @@ mod tests {
         assert!(cmd.next().is_none());
     }
 
+    #[test]
+    fn it_splits_the_motion_longer_than_the_command_field() {
+        let mut cmd = move_paper(5000);
+
+        let first = SendBytes(u16::from_be_bytes([0xdf, 0xff]));
+        let second = SendBytes(u16::from_be_bytes([0xd3, 0x89]));
+        assert_eq!(cmd.next().unwrap(), first);
+        assert_eq!(cmd.next().unwrap(), second);
+        assert!(cmd.next().is_none());
+    }
+
+    #[test]
+    fn it_splits_the_longest_motion_leftwards() {
+        let last = SendBytes(u16::from_be_bytes([0xe0, 0x08]));
+        assert_eq!(move_carriage(i16::MIN).count(), 9);
+        assert_eq!(move_carriage(i16::MIN).last().unwrap(), last);
+    }
+
+    #[test]
+    fn it_rejects_the_motion_out_of_range() {
+        let cmd = try_move_relative(0, 40_000);
+
+        assert_eq!(cmd.err(), Some(MotionOutOfRange(40_000)));
+    }
+
     // #[test]
     // fn test_plus_y() {
     //     let data = Cmd::Motion(CmdMotion::plus_y(0x514));
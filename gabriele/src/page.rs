@@ -0,0 +1,71 @@
+use crate::position::Position;
+use crate::resolution::DEFAULT_Y_RESOLUTION;
+
+/// Length of A4 paper sheet in typewriter units, 70 lines with the default resolution.
+pub const A4_LENGTH: i32 = 70 * DEFAULT_Y_RESOLUTION;
+
+/// Vertical geometry of a paper sheet in typewriter units.
+///
+/// The upper edge of the sheet is at `y == 0`, the lines are printed
+/// between the `top_margin` and the `length - bottom_margin`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Page {
+    pub length: i32,
+    pub top_margin: i32,
+    pub bottom_margin: i32,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            length: A4_LENGTH,
+            top_margin: 6 * DEFAULT_Y_RESOLUTION,
+            bottom_margin: 6 * DEFAULT_Y_RESOLUTION,
+        }
+    }
+}
+
+impl Page {
+    /// Number of lines fitting between the margins when the line height is `line_height`.
+    pub fn lines(&self, line_height: i32) -> i32 {
+        (self.length - self.top_margin - self.bottom_margin) / line_height
+    }
+
+    /// Whether the line at the `pos` would go beyond the bottom margin.
+    pub fn is_full(&self, pos: &Position) -> bool {
+        pos.y + pos.res.y > self.length - self.bottom_margin
+    }
+
+    /// Paper motion which ejects the sheet when the carriage is at the `pos`.
+    pub fn eject(&self, pos: &Position) -> i32 {
+        (self.length - pos.y).max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolution::DEFAULT_Y_RESOLUTION as Y_RES;
+
+    #[test]
+    fn test_default_page_holds_58_lines() {
+        assert_eq!(Page::default().lines(Y_RES), 58);
+    }
+
+    #[test]
+    fn test_page_is_full_below_the_last_line() {
+        let page = Page {
+            length: 10 * Y_RES,
+            top_margin: Y_RES,
+            bottom_margin: 2 * Y_RES,
+        };
+        let mut pos = Position {
+            y: 7 * Y_RES,
+            ..Default::default()
+        };
+        assert!(!page.is_full(&pos));
+        pos.update_y(1);
+        assert!(page.is_full(&pos));
+        assert_eq!(page.eject(&pos), 2 * Y_RES);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::cmd::Cmd;
+use crate::printing::Instruction;
+
+/// The pauses in milliseconds the Machine makes with `Instruction::Idle`,
+/// no pause is made when the value is zero.
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub struct Pauses {
+    /// Pause after every carriage or paper motion per 100 typewriter units of its distance,
+    /// it gives the typewriter time to finish the long carriage returns and paper moves
+    pub motion: u32,
+
+    /// Pause after a new sheet is inserted, before the next page is printed
+    pub page: u32,
+
+    /// Pause after every command, it paces the output for the typewriters
+    /// which drop bytes under sustained load
+    pub pacing: u32,
+}
+
+impl Pauses {
+    /// The pause to be made once the `instruction` is sent.
+    pub fn after(&self, instruction: &Instruction) -> u32 {
+        let Instruction::SendBytes(word) = *instruction else {
+            return 0;
+        };
+        let motion = match Cmd::from_u16(word) {
+            Ok(Cmd::Motion(motion)) => (u32::from(motion.value) * self.motion).div_ceil(100),
+            _ => 0,
+        };
+        self.pacing + motion
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::{CmdJump, CmdMotion};
+
+    fn motion(cmd: CmdMotion) -> Instruction {
+        Cmd::Motion(cmd).as_instruction()
+    }
+
+    #[test]
+    fn test_no_pauses_by_default() {
+        let pauses = Pauses::default();
+        assert_eq!(pauses.after(&motion(CmdMotion::minus_x(1560))), 0);
+    }
+
+    #[test]
+    fn test_pause_grows_with_the_distance() {
+        let pauses = Pauses {
+            motion: 10,
+            ..Default::default()
+        };
+        assert_eq!(pauses.after(&motion(CmdMotion::minus_x(1560))), 156);
+        assert_eq!(pauses.after(&motion(CmdMotion::plus_y(16))), 2);
+        assert_eq!(pauses.after(&Cmd::Jump(CmdJump::Plus).as_instruction()), 0);
+    }
+
+    #[test]
+    fn test_pacing_follows_every_command() {
+        let pauses = Pauses {
+            motion: 10,
+            pacing: 5,
+            ..Default::default()
+        };
+        assert_eq!(pauses.after(&motion(CmdMotion::plus_y(16))), 7);
+        assert_eq!(pauses.after(&Cmd::Jump(CmdJump::Plus).as_instruction()), 5);
+        assert_eq!(pauses.after(&Instruction::Halt), 0);
+    }
+}
@@ -1,11 +1,14 @@
 #![allow(unused)]
 #![allow(dead_code)]
 
+use crate::attributes::Attributes;
+use crate::error::Error;
 use crate::machine::{PrintingDirection, Settings};
-use crate::motion;
+use crate::motion::{self, MotionOutOfRange};
 use crate::position::Position;
-use crate::resolution::Resolution;
-use crate::symbol::{ActionMapping, Symbol};
+use crate::resolution::{Resolution, DEFAULT_X_RESOLUTION};
+use crate::sign::Sign;
+use crate::symbol::{ActionMapping, AfterSymbolPrinted, Symbol};
 use either::Either;
 
 /// The basic directive for the machine
@@ -14,6 +17,7 @@ use either::Either;
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Instruction {
     SendBytes(u16),
+    Idle(u32),
     Halt,
 }
 
@@ -39,6 +43,7 @@ pub struct Action<'a> {
     pub settings: &'a Settings,
     pub repeat: usize,
     pub current_position: &'a Position,
+    pub attributes: Attributes,
 }
 
 impl<'a> Action<'a> {
@@ -53,15 +58,68 @@ impl<'a> Action<'a> {
             settings,
             repeat,
             current_position,
+            attributes: Default::default(),
         }
     }
 
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Carriage travel of the Symbol in typewriter units when it is made by an explicit motion
+    /// instead of the typewriter's own step, which is the case for the proportional Symbols
+    /// and for any pitch other than `DEFAULT_X_RESOLUTION`.
+    pub fn explicit_width(&self) -> Option<i32> {
+        let res = self.current_position.res.x;
+        let places = match self.symbol.act {
+            ActionMapping::Whitespace => 1,
+            _ => self.symbol.x_positions_increment(),
+        };
+        match self.symbol.width {
+            Some(width) => Some(width),
+            None if res != DEFAULT_X_RESOLUTION => Some(places * res),
+            None => None,
+        }
+    }
+
+    /// Instructions for a printed Symbol with respect to the text attributes,
+    /// fails when the bold offset or the explicit width does not fit into a motion.
+    pub fn print_instructions(&self) -> Result<impl Iterator<Item = Instruction>, Error> {
+        let (bold, advance) = self.strike_motions()?;
+        Ok(strikes(
+            self.symbol,
+            self.attributes,
+            self.settings.direction,
+            bold,
+            advance,
+        ))
+    }
+
+    /// The shift of the bold strike and the carriage advance after the strikes
+    /// made by explicit motions, see `strikes`.
+    fn strike_motions(&self) -> Result<(Option<i16>, Option<i16>), Error> {
+        let bold = match self.attributes.bold {
+            true => Some(distance(self.settings.bold_offset)?),
+            false => None,
+        };
+        let direction = i32::from(self.settings.direction);
+        let advance = self
+            .explicit_width()
+            .map(|width| distance(width * direction))
+            .transpose()?;
+        Ok((bold, advance))
+    }
+
     pub fn whitespace_instructions(
         &self,
         old_position: &Position,
         new_position: &Position,
-    ) -> impl Iterator<Item = Instruction> {
-        match self.settings.direction {
+    ) -> Result<impl Iterator<Item = Instruction>, Error> {
+        Ok(match self.settings.direction {
+            _ if self.explicit_width().is_some() => Either::Right(Either::Right(
+                motion::move_absolute(old_position, new_position)?,
+            )),
             PrintingDirection::Left if (self.repeat == 1) => {
                 Either::Left(motion::space_jump_left())
             }
@@ -71,23 +129,50 @@ impl<'a> Action<'a> {
             _ => Either::Right(Either::Right(motion::move_absolute(
                 old_position,
                 new_position,
-            ))),
-        }
+            )?)),
+        })
     }
 
     /// Generates a sequence of the Instructions,
     /// taking the current Position as a reference point.
     /// The result of these instructions is the printed Symbol or/and the associated motion.
-    pub fn instructions(self, new_position: &Position) -> impl Iterator<Item = Instruction> {
-        match self.symbol.act {
-            ActionMapping::Print => Either::Left(self.symbol.instructions(self.settings.direction)),
-            ActionMapping::Whitespace => Either::Right(Either::Left(
-                self.whitespace_instructions(self.current_position, new_position),
-            )),
-            ActionMapping::LineFeed => Either::Right(Either::Right(
-                (motion::move_absolute(self.current_position, new_position)),
-            )),
+    /// Fails when a sign is beyond the daisy wheel
+    /// or the motion to the `new_position` does not fit into `i16`.
+    pub fn instructions(
+        self,
+        new_position: &Position,
+    ) -> Result<impl Iterator<Item = Instruction>, Error> {
+        self.symbol.check()?;
+        for mark in self.attributes.marks().iter().flatten() {
+            mark.check()?;
         }
+        Ok(match self.symbol.act {
+            ActionMapping::Print => Either::Left(Either::Left(self.print_instructions()?)),
+            ActionMapping::Whitespace if self.attributes.is_overstruck() => {
+                let (symbol, attributes) = (self.symbol, self.attributes);
+                let direction = self.settings.direction;
+                let (bold, advance) = self.strike_motions()?;
+                let marks = (0..self.repeat)
+                    .flat_map(move |_| strikes(symbol, attributes, direction, bold, advance));
+                Either::Left(Either::Right(marks))
+            }
+            ActionMapping::Whitespace => Either::Right(Either::Left(Either::Right(
+                self.whitespace_instructions(self.current_position, new_position)?,
+            ))),
+            ActionMapping::Backspace
+                if self.repeat == 1 && self.current_position.res.x == DEFAULT_X_RESOLUTION =>
+            {
+                Either::Right(Either::Left(Either::Left(motion::space_jump_left())))
+            }
+            ActionMapping::LineFeed
+            | ActionMapping::Tab
+            | ActionMapping::CarriageReturn
+            | ActionMapping::FormFeed
+            | ActionMapping::Backspace => Either::Right(Either::Right(motion::move_absolute(
+                self.current_position,
+                new_position,
+            )?)),
+        })
     }
 
     /// New position represents a calculated desired Position
@@ -96,33 +181,125 @@ impl<'a> Action<'a> {
     pub fn target_position(&self) -> Position {
         let mut pos = *self.current_position;
         match self.symbol.act {
-            ActionMapping::Print => match self.settings.direction {
-                PrintingDirection::Right => pos.update_x(self.symbol.x_positions_increment()),
-                PrintingDirection::Left => pos.update_x(-self.symbol.x_positions_increment()),
+            ActionMapping::Print => match (self.symbol.width, self.settings.direction) {
+                (Some(width), direction) => pos.x += width * i32::from(direction),
+                (None, PrintingDirection::Right) => {
+                    pos.update_x(self.symbol.x_positions_increment())
+                }
+                (None, PrintingDirection::Left) => {
+                    pos.update_x(-self.symbol.x_positions_increment())
+                }
             },
 
             ActionMapping::Whitespace => {
-                pos.update_x(self.repeat as i32 * i32::from(self.settings.direction))
+                let places = self.repeat as i32 * i32::from(self.settings.direction);
+                match self.symbol.width {
+                    Some(width) => pos.x += width * places,
+                    None => pos.update_x(places),
+                }
+            }
+
+            ActionMapping::LineFeed => {
+                pos.apply_line_feed(self.repeat as i32);
+                pos.x = self.settings.left_margin;
             }
 
-            ActionMapping::LineFeed => pos.apply_line_feed(self.repeat as i32),
+            ActionMapping::Tab => {
+                let left_margin = self.settings.left_margin;
+                // the stops cannot be closer than one unit
+                let tab_stops = self.settings.tab_stops.max(1);
+                let column = (pos.x - left_margin).div_euclid(tab_stops);
+                pos.x = left_margin + (column + self.repeat as i32) * tab_stops;
+            }
+
+            ActionMapping::CarriageReturn => pos.x = self.settings.left_margin,
+
+            ActionMapping::FormFeed => {
+                let length = self.settings.page.unwrap_or_default().length;
+                pos.y = (pos.y.div_euclid(length) + 1) * length;
+                pos.x = self.settings.left_margin;
+            }
+
+            ActionMapping::Backspace => pos.update_x(-(self.repeat as i32)),
         };
         pos
     }
 }
 
+/// Converts the distance in typewriter units of a motion made along with the strikes.
+fn distance(units: i32) -> Result<i16, Error> {
+    i16::try_from(units).map_err(|_| MotionOutOfRange(units).into())
+}
+
+/// Strikes all the signs of the Symbol and the overstrike marks of the text attributes
+/// on a single character place, only the last strike moves the carriage.
+/// Every `bold` sign is struck again shifted by its offset.
+/// With the explicit `advance` all the strikes hold the carriage on and it is moved afterwards.
+fn strikes(
+    symbol: &'static Symbol,
+    attributes: Attributes,
+    direction: PrintingDirection,
+    bold: Option<i16>,
+    advance: Option<i16>,
+) -> impl Iterator<Item = Instruction> {
+    let last_moves = attributes.is_overstruck() || advance.is_some();
+    let after = match (&symbol.act, symbol.x_positions_increment()) {
+        _ if advance.is_some() => AfterSymbolPrinted::HoldOn,
+        (ActionMapping::Whitespace, _) => AfterSymbolPrinted::MoveRight,
+        (_, 1..) => AfterSymbolPrinted::MoveRight,
+        (_, ..0) => AfterSymbolPrinted::MoveLeft,
+        (_, 0) => AfterSymbolPrinted::HoldOn,
+    };
+    let mut signs = symbol
+        .signs
+        .iter()
+        .flatten()
+        .copied()
+        .chain(attributes.marks().into_iter().flatten())
+        .peekable();
+    let signs = core::iter::from_fn(move || {
+        let sign = signs.next()?;
+        Some(match (last_moves, signs.peek()) {
+            (false, _) => sign,
+            (true, Some(_)) => Sign {
+                after: AfterSymbolPrinted::HoldOn,
+                ..sign
+            },
+            (true, None) => Sign { after, ..sign },
+        })
+    });
+
+    signs
+        .flat_map(move |sign| match bold {
+            Some(offset) => Either::Left(sign.bold_instructions(direction, offset)),
+            None => Either::Right(core::iter::once(sign.build_instruction(direction))),
+        })
+        .chain(motion::move_relative(advance.unwrap_or_default(), 0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::Action;
+    use crate::attributes::Attributes;
+    use crate::error::Error;
     use crate::machine::Settings;
+    use crate::motion::MotionOutOfRange;
+    use crate::page::Page;
     use crate::position::Position;
     use crate::printing::Instruction;
     use crate::printing::Instruction::SendBytes;
-    use crate::resolution::Resolution;
+    use crate::resolution::{Pitch, Resolution};
     use crate::symbol::Symbol;
 
     static U_UMLAUT_SYMBOL: Symbol = Symbol::new('ü').petal(81);
     static LINE_FEED_SYMBOL: Symbol = Symbol::line_feed();
+    static TAB_SYMBOL: Symbol = Symbol::tab();
+    static CARRIAGE_RETURN_SYMBOL: Symbol = Symbol::carriage_return();
+    static FORM_FEED_SYMBOL: Symbol = Symbol::form_feed();
+    static BACKSPACE_SYMBOL: Symbol = Symbol::backspace();
+    static WHITESPACE_SYMBOL: Symbol = Symbol::whitespace();
+    static BROKEN_SYMBOL: Symbol = Symbol::new('x').petal(120);
+    static PROPORTIONAL_SYMBOL: Symbol = Symbol::new('m').petal(6).width(16);
 
     #[test]
     fn test_print_symbol() {
@@ -131,7 +308,7 @@ mod tests {
         let settings = Settings::default();
         let action = Action::new(&U_UMLAUT_SYMBOL, &settings, 1, &pos);
         let target = action.target_position();
-        let mut commands = action.instructions(&target);
+        let mut commands = action.instructions(&target).unwrap();
         let pos_diff = target.diff(&pos);
 
         assert_eq!(pos_diff, (12, 0));
@@ -146,7 +323,7 @@ mod tests {
     #[test]
     fn test_line_feed_coordinates() {
         let base_pos: Position = Default::default();
-        let mut pos = base_pos.clone();
+        let mut pos = base_pos;
         // emulate the motion result caused by printing of 10 characters
         // causing the carriage to move by X=+120 units, when Y=0
         pos.update_x(10);
@@ -155,7 +332,7 @@ mod tests {
         let settings = Settings::default();
         let action: Action = Action::new(&LINE_FEED_SYMBOL, &settings, 1, &pos);
         let target = action.target_position();
-        let mut commands = action.instructions(&target);
+        let mut commands = action.instructions(&target).unwrap();
 
         assert!(commands.next().is_some());
 
@@ -168,13 +345,13 @@ mod tests {
     #[test]
     fn test_line_feed_instructions() {
         let base_pos: Position = Default::default();
-        let mut pos = base_pos.clone();
+        let mut pos = base_pos;
         pos.update_x(10);
 
         let settings = Settings::default();
         let action = Action::new(&LINE_FEED_SYMBOL, &settings, 1, &pos);
         let target = action.target_position();
-        let mut commands = action.instructions(&target);
+        let mut commands = action.instructions(&target).unwrap();
         let pos_diff = target.diff(&pos);
 
         let details = u16::from_be_bytes([0b1110_0000, 120]);
@@ -184,4 +361,178 @@ mod tests {
         assert_eq!(commands.next(), Some(Instruction::SendBytes(details)));
         assert_eq!(commands.next(), None);
     }
+
+    #[test]
+    fn test_petal_beyond_the_wheel() {
+        let pos: Position = Default::default();
+
+        let settings = Settings::default();
+        let action = Action::new(&BROKEN_SYMBOL, &settings, 1, &pos);
+        let target = action.target_position();
+
+        assert_eq!(
+            action.instructions(&target).err(),
+            Some(Error::InvalidPetal(120))
+        );
+    }
+
+    #[test]
+    fn test_line_feeds_out_of_range() {
+        let pos: Position = Default::default();
+
+        let settings = Settings::default();
+        let action = Action::new(&LINE_FEED_SYMBOL, &settings, 3000, &pos);
+        let target = action.target_position();
+
+        assert_eq!(
+            action.instructions(&target).err(),
+            Some(Error::MotionOutOfRange(MotionOutOfRange(48_000)))
+        );
+    }
+
+    #[test]
+    fn test_bold_offset_out_of_range() {
+        let pos: Position = Default::default();
+
+        let settings = Settings {
+            bold_offset: 40_000,
+            ..Default::default()
+        };
+        let attributes = Attributes {
+            bold: true,
+            ..Default::default()
+        };
+        let action = Action::new(&U_UMLAUT_SYMBOL, &settings, 1, &pos).with_attributes(attributes);
+        let target = action.target_position();
+
+        assert_eq!(
+            action.instructions(&target).err(),
+            Some(Error::MotionOutOfRange(MotionOutOfRange(40_000)))
+        );
+    }
+
+    #[test]
+    fn test_tab_goes_to_the_next_stop() {
+        let mut pos: Position = Default::default();
+        pos.update_x(3);
+
+        let settings = Settings {
+            left_margin: 12,
+            tab_stops: 48,
+            ..Default::default()
+        };
+        let action = Action::new(&TAB_SYMBOL, &settings, 1, &pos);
+        assert_eq!(action.target_position().x, 60);
+
+        let action = Action::new(&TAB_SYMBOL, &settings, 2, &pos);
+        assert_eq!(action.target_position().x, 108);
+    }
+
+    #[test]
+    fn test_tab_stops_are_at_least_one_unit_apart() {
+        let mut pos: Position = Default::default();
+        pos.update_x(3);
+
+        for tab_stops in [0, -48] {
+            let settings = Settings {
+                left_margin: 12,
+                tab_stops,
+                ..Default::default()
+            };
+            let action = Action::new(&TAB_SYMBOL, &settings, 2, &pos);
+            assert_eq!(action.target_position().x, 38);
+        }
+    }
+
+    #[test]
+    fn test_carriage_return_keeps_the_line() {
+        let mut pos: Position = Default::default();
+        pos.update_x(10);
+        pos.update_y(2);
+
+        let settings = Settings {
+            left_margin: 24,
+            ..Default::default()
+        };
+        let action = Action::new(&CARRIAGE_RETURN_SYMBOL, &settings, 1, &pos);
+        let target = action.target_position();
+        let mut commands = action.instructions(&target).unwrap();
+
+        assert_eq!(target.diff(&pos), (-96, 0));
+        let details = u16::from_be_bytes([0b1110_0000, 96]);
+        assert_eq!(commands.next(), Some(SendBytes(details)));
+        assert_eq!(commands.next(), None);
+    }
+
+    #[test]
+    fn test_form_feed_goes_to_the_next_page() {
+        let mut pos: Position = Default::default();
+        pos.update_x(10);
+        pos.update_y(2);
+
+        let settings = Settings {
+            page: Some(Page {
+                length: 160,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let action = Action::new(&FORM_FEED_SYMBOL, &settings, 1, &pos);
+        assert_eq!(action.target_position().diff(&pos), (-120, 128));
+    }
+
+    #[test]
+    fn test_backspace_steps_back() {
+        let mut pos: Position = Default::default();
+        pos.update_x(10);
+
+        let settings = Settings::default();
+        let action = Action::new(&BACKSPACE_SYMBOL, &settings, 1, &pos);
+        let target = action.target_position();
+        let mut commands = action.instructions(&target).unwrap();
+
+        assert_eq!(target.diff(&pos), (-12, 0));
+        assert_eq!(commands.next(), Some(SendBytes(0x8400)));
+        assert_eq!(commands.next(), None);
+
+        let action = Action::new(&BACKSPACE_SYMBOL, &settings, 3, &pos);
+        assert_eq!(action.target_position().diff(&pos), (-36, 0));
+    }
+
+    #[test]
+    fn test_proportional_symbol_moves_by_its_width() {
+        let pos: Position = Default::default();
+
+        let settings = Settings::default();
+        let action = Action::new(&PROPORTIONAL_SYMBOL, &settings, 1, &pos);
+        let target = action.target_position();
+        let mut commands = action.instructions(&target).unwrap();
+
+        assert_eq!(target.diff(&pos), (16, 0));
+        // Normal impression, hold on
+        assert_eq!(
+            commands.next(),
+            Some(SendBytes(u16::from_be_bytes([6, 31])))
+        );
+        assert_eq!(commands.next(), Some(SendBytes(0xc010)));
+        assert_eq!(commands.next(), None);
+    }
+
+    #[test]
+    fn test_whitespace_follows_the_pitch() {
+        let pos = Position {
+            res: Resolution::new(Pitch::Micro, Default::default()),
+            ..Default::default()
+        };
+
+        let settings = Settings::default();
+        let action = Action::new(&WHITESPACE_SYMBOL, &settings, 1, &pos);
+        let target = action.target_position();
+        let mut commands = action.instructions(&target).unwrap();
+
+        // no space jump, the typewriter's own step is wider
+        assert_eq!(target.diff(&pos), (8, 0));
+        assert_eq!(commands.next(), Some(SendBytes(0xc008)));
+        assert_eq!(commands.next(), None);
+    }
 }
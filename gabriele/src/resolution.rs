@@ -15,3 +15,55 @@ impl Default for Resolution {
         }
     }
 }
+
+impl Resolution {
+    pub fn new(pitch: Pitch, spacing: LineSpacing) -> Self {
+        Resolution {
+            x: pitch.x(),
+            y: spacing.y(),
+        }
+    }
+}
+
+/// Number of characters per inch, the typewriter's own step is `Pica`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Pitch {
+    /// 10 characters per inch
+    #[default]
+    Pica,
+    /// 12 characters per inch
+    Elite,
+    /// 15 characters per inch
+    Micro,
+}
+
+impl Pitch {
+    /// Width of a character place in typewriter units.
+    pub fn x(&self) -> i32 {
+        match self {
+            Pitch::Pica => DEFAULT_X_RESOLUTION,
+            Pitch::Elite => 10,
+            Pitch::Micro => 8,
+        }
+    }
+}
+
+/// Distance between the lines, the single one is 6 lines per inch.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LineSpacing {
+    #[default]
+    Single,
+    OneAndHalf,
+    Double,
+}
+
+impl LineSpacing {
+    /// Height of a line in typewriter units.
+    pub fn y(&self) -> i32 {
+        match self {
+            LineSpacing::Single => DEFAULT_Y_RESOLUTION,
+            LineSpacing::OneAndHalf => DEFAULT_Y_RESOLUTION * 3 / 2,
+            LineSpacing::Double => DEFAULT_Y_RESOLUTION * 2,
+        }
+    }
+}
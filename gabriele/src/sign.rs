@@ -1,5 +1,7 @@
 use crate::cmd::{Cmd, Impression};
+use crate::error::Error;
 use crate::machine::PrintingDirection;
+use crate::motion;
 use crate::printing::Instruction;
 use crate::symbol::{AfterSymbolPrinted, CmdSymbol, SymbolPrintingAttrs};
 
@@ -10,27 +12,45 @@ use crate::symbol::{AfterSymbolPrinted, CmdSymbol, SymbolPrintingAttrs};
 /// 2. servo motor rotates the daisy wheel to a specific `idx` position;
 /// 3. solenoid-operated hammer hits the selected petal with a force represented by `imp`;
 /// 4. after the character is printed, `after` determines the behavior of carriage motor.
-#[derive(PartialEq, Debug, Clone, Default)]
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
 pub struct Sign {
     pub idx: u8,
     pub imp: Impression,
     pub after: AfterSymbolPrinted,
 }
 
+/// The highest petal index on a daisy wheel.
+pub const MAX_PETAL: u8 = 100;
+
 impl Sign {
     /// Build a single `Instruction` for the `Sign` taking ito account
     /// current `PrintingDirection`
+    ///
+    /// # Panics
+    ///
+    /// Panics when the petal index is beyond `MAX_PETAL`, see `try_build_instruction`.
     pub fn build_instruction(&self, dir: PrintingDirection) -> Instruction {
+        self.try_build_instruction(dir)
+            .expect("not a valid petal index")
+    }
+
+    /// Fails when the petal index is beyond `MAX_PETAL`.
+    pub fn check(&self) -> Result<(), Error> {
+        match self.idx {
+            0..=MAX_PETAL => Ok(()),
+            idx => Err(Error::InvalidPetal(idx)),
+        }
+    }
+
+    /// Same as `build_instruction`, fails when the petal index is beyond `MAX_PETAL`.
+    pub fn try_build_instruction(&self, dir: PrintingDirection) -> Result<Instruction, Error> {
+        self.check()?;
         let b1 = self.idx;
         let attr = SymbolPrintingAttrs {
             direction: self.after.with_direction(dir),
             impression: self.imp,
         };
 
-        if b1 > 100 {
-            panic!("not a valid petal index")
-        }
-
         let cmd = if b1 > 0x3f {
             Cmd::SymbolHigh(CmdSymbol {
                 code: b1 & 0x3f,
@@ -40,6 +60,24 @@ impl Sign {
             Cmd::SymbolLow(CmdSymbol { code: b1, attr })
         };
 
-        cmd.as_instruction()
+        cmd.try_as_instruction()
+    }
+
+    /// Strikes the `Sign` twice, the second time shifted by the `offset` in typewriter units,
+    /// then moves the carriage back by the `offset` to keep it on the character grid.
+    pub fn bold_instructions(
+        &self,
+        dir: PrintingDirection,
+        offset: i16,
+    ) -> impl Iterator<Item = Instruction> {
+        let first = Sign {
+            after: AfterSymbolPrinted::HoldOn,
+            ..*self
+        };
+        [first.build_instruction(dir)]
+            .into_iter()
+            .chain(motion::move_relative(offset, 0))
+            .chain([self.build_instruction(dir)])
+            .chain(motion::move_relative(-offset, 0))
     }
 }
@@ -1,8 +1,10 @@
 use crate::cmd::Impression;
+use crate::error::Error;
 use crate::machine::PrintingDirection;
 use crate::printing::Instruction;
 use crate::sign::Sign;
-use deku::DekuWrite;
+use core::fmt;
+use deku::{DekuRead, DekuWrite};
 
 #[derive(PartialEq, Debug, Clone, Default)]
 pub enum ActionMapping {
@@ -10,9 +12,17 @@ pub enum ActionMapping {
     Print,
     Whitespace,
     LineFeed,
+    /// Moves the carriage to the next tab stop
+    Tab,
+    /// Returns the carriage to the left margin without a line feed
+    CarriageReturn,
+    /// Moves the paper to the beginning of the next page
+    FormFeed,
+    /// Moves the carriage one character place back
+    Backspace,
 }
 
-#[derive(PartialEq, Debug, Copy, Clone, Default, DekuWrite)]
+#[derive(PartialEq, Debug, Copy, Clone, Default, DekuRead, DekuWrite)]
 #[deku(id_type = "u8", bits = 2)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
@@ -31,7 +41,7 @@ pub enum AfterSymbolPrinted {
     HoldOn,
 }
 
-#[derive(PartialEq, Debug, Clone, Default, DekuWrite, Copy)]
+#[derive(PartialEq, Debug, Clone, Default, DekuRead, DekuWrite, Copy)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
 pub struct SymbolPrintingAttrs {
@@ -39,7 +49,7 @@ pub struct SymbolPrintingAttrs {
     pub impression: Impression,
 }
 
-#[derive(DekuWrite, PartialEq, Debug, Clone, Copy, Default)]
+#[derive(DekuRead, DekuWrite, PartialEq, Debug, Clone, Copy, Default)]
 #[deku(endian = "big")]
 #[deku(ctx = "endian: deku::ctx::Endian")]
 pub struct CmdSymbol {
@@ -65,11 +75,24 @@ impl AfterSymbolPrinted {
     }
 }
 
+impl fmt::Display for AfterSymbolPrinted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AfterSymbolPrinted::MoveRight => write!(f, "move right"),
+            AfterSymbolPrinted::MoveLeft => write!(f, "move left"),
+            AfterSymbolPrinted::HoldOn => write!(f, "hold on"),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone, Default)]
 pub struct Symbol {
     pub signs: [Option<Sign>; 2],
     pub character: char,
     pub act: ActionMapping,
+    /// Advance width in typewriter units for the proportional spacing,
+    /// the Symbols without it take the fixed character place of `Resolution::x`
+    pub width: Option<i32>,
 }
 
 impl Symbol {
@@ -78,6 +101,7 @@ impl Symbol {
             character,
             signs: [None, None],
             act: ActionMapping::Print,
+            width: None,
         }
     }
 
@@ -86,6 +110,10 @@ impl Symbol {
             ActionMapping::Print => false,
             ActionMapping::Whitespace => true,
             ActionMapping::LineFeed => true,
+            ActionMapping::Tab => true,
+            ActionMapping::CarriageReturn => false,
+            ActionMapping::FormFeed => false,
+            ActionMapping::Backspace => true,
         }
     }
 
@@ -141,6 +169,35 @@ impl Symbol {
         item
     }
 
+    pub const fn tab() -> Self {
+        let mut item = Self::new('\t');
+        item.act = ActionMapping::Tab;
+        item
+    }
+
+    pub const fn carriage_return() -> Self {
+        let mut item = Self::new('\r');
+        item.act = ActionMapping::CarriageReturn;
+        item
+    }
+
+    pub const fn form_feed() -> Self {
+        let mut item = Self::new('\x0c');
+        item.act = ActionMapping::FormFeed;
+        item
+    }
+
+    pub const fn backspace() -> Self {
+        let mut item = Self::new('\x08');
+        item.act = ActionMapping::Backspace;
+        item
+    }
+
+    pub const fn width(mut self, width: i32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
     pub const fn imp(mut self, impression: Impression) -> Self {
         if let Some(ref mut sign) = self.signs[0] {
             sign.imp = impression
@@ -169,6 +226,23 @@ impl Symbol {
             .map(move |sign| sign.build_instruction(direction))
     }
 
+    /// Strikes every sign twice to make the Symbol look bold, see `Sign::bold_instructions`.
+    pub fn bold_instructions(
+        &self,
+        direction: PrintingDirection,
+        offset: i16,
+    ) -> impl Iterator<Item = Instruction> + use<'_> {
+        self.signs
+            .iter()
+            .flatten()
+            .flat_map(move |sign| sign.bold_instructions(direction, offset))
+    }
+
+    /// Fails when any of the signs is beyond the daisy wheel.
+    pub fn check(&self) -> Result<(), Error> {
+        self.signs.iter().flatten().try_for_each(Sign::check)
+    }
+
     pub fn x_positions_increment(&self) -> i32 {
         let mut x = 0_i32;
         for sign in self.signs.iter().flatten() {
@@ -214,4 +288,29 @@ mod tests {
         );
         assert_eq!(result.next(), None);
     }
+
+    #[test]
+    fn test_bold_instructions() {
+        let symbol = Symbol::new('ü').petal(81);
+        let mut result = symbol.bold_instructions(PrintingDirection::Left, 2);
+        // Normal impression, hold on
+        assert_eq!(
+            result.next(),
+            Some(Instruction::SendBytes(u16::from_be_bytes([81, 31])))
+        );
+        assert_eq!(
+            result.next(),
+            Some(Instruction::SendBytes(u16::from_be_bytes([0xc0, 2])))
+        );
+        // Normal impression, move left
+        assert_eq!(
+            result.next(),
+            Some(Instruction::SendBytes(u16::from_be_bytes([81, 31 + 192])))
+        );
+        assert_eq!(
+            result.next(),
+            Some(Instruction::SendBytes(u16::from_be_bytes([0xe0, 2])))
+        );
+        assert_eq!(result.next(), None);
+    }
 }
@@ -1,18 +1,50 @@
-use crate::database::DaisyDatabase;
+use crate::database::{DaisyDatabase, Lookup, UnknownCharacter, UnknownCharacterPolicy};
 use crate::symbol::Symbol;
 
 pub trait ToSymbols {
+    /// Converts the input into Symbols, the characters which cannot be printed are left out.
+    /// The input should be checked with `unknown_characters` beforehand.
     fn to_symbols<'a>(
         &'a self,
         db: &'a impl DaisyDatabase,
+        policy: UnknownCharacterPolicy,
     ) -> impl Iterator<Item = &'static Symbol> + 'a;
+
+    /// Lists all the characters which cannot be printed under the `policy`.
+    fn unknown_characters<'a>(
+        &'a self,
+        db: &'a impl DaisyDatabase,
+        policy: UnknownCharacterPolicy,
+    ) -> impl Iterator<Item = UnknownCharacter> + 'a;
 }
 
-impl ToSymbols for &str {
+impl ToSymbols for str {
     fn to_symbols<'a>(
         &'a self,
         db: &'a impl DaisyDatabase,
+        policy: UnknownCharacterPolicy,
     ) -> impl Iterator<Item = &'static Symbol> + 'a {
-        self.chars().map(move |chr| db.get(chr))
+        self.chars()
+            .filter_map(move |chr| match policy.lookup(db, chr) {
+                Lookup::Found(symbol) => Some(symbol),
+                _ => None,
+            })
+    }
+
+    fn unknown_characters<'a>(
+        &'a self,
+        db: &'a impl DaisyDatabase,
+        policy: UnknownCharacterPolicy,
+    ) -> impl Iterator<Item = UnknownCharacter> + 'a {
+        self.lines().enumerate().flat_map(move |(line, text)| {
+            text.chars()
+                .enumerate()
+                .filter(move |(_, chr)| policy.lookup(db, *chr) == Lookup::Unknown)
+                .map(move |(column, character)| UnknownCharacter {
+                    character,
+                    line: line + 1,
+                    column: column + 1,
+                })
+        })
     }
 }
@@ -0,0 +1,97 @@
+use crate::machine::InstructionSender;
+use crate::printing::Instruction;
+use crate::Error;
+use core::cell::Cell;
+use core::fmt;
+
+/// The outcome of a dry run of the Machine over an input text.
+/// Nothing is sent to the typewriter while the report is made,
+/// the details can be listed with `Machine::unknown_characters` and `Machine::overruns`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Report {
+    /// Number of characters which cannot be printed
+    pub unknown_characters: usize,
+
+    /// Number of lines which do not fit into the carriage travel
+    pub overruns: usize,
+
+    /// Number of Instructions to be sent to the typewriter
+    pub instructions: usize,
+
+    /// Number of paper sheets to be printed on with respect to the page geometry
+    /// in the Settings, nothing is counted for the endless paper
+    pub sheets: usize,
+
+    /// The error which stops the printing partway, the counts above
+    /// cover the input up to it
+    pub error: Option<Error>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.unknown_characters == 0 && self.overruns == 0 && self.error.is_none()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} unknown characters, {} lines overrun the carriage, {} instructions",
+            self.unknown_characters, self.overruns, self.instructions
+        )?;
+        if self.sheets > 0 {
+            write!(f, ", {} sheets of paper", self.sheets)?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, ", the printing stops: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A line of the input which does not fit into the carriage travel,
+/// `line` is counted from 1 and `end` is the carriage position
+/// in typewriter units after the line is printed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Overrun {
+    pub line: usize,
+    pub end: i32,
+}
+
+impl fmt::Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} overruns the carriage, it ends at {}",
+            self.line, self.end
+        )
+    }
+}
+
+/// Counts the Instructions and the paper changes instead of sending them.
+#[derive(Default)]
+pub(crate) struct InstructionCounter {
+    instructions: Cell<usize>,
+    paper_changes: Cell<usize>,
+}
+
+impl InstructionCounter {
+    pub(crate) fn count(&self) -> usize {
+        self.instructions.get()
+    }
+
+    pub(crate) fn paper_changes(&self) -> usize {
+        self.paper_changes.get()
+    }
+}
+
+impl InstructionSender for InstructionCounter {
+    async fn send(&self, _instr: Instruction) {
+        self.instructions.set(self.instructions.get() + 1);
+    }
+
+    async fn change_paper(&self) {
+        self.paper_changes.set(self.paper_changes.get() + 1);
+    }
+}
@@ -1 +1,2 @@
+pub mod proportional;
 pub mod standard;
@@ -0,0 +1,134 @@
+use crate::symbol::Symbol;
+
+/// The petals of the proportional spacing (PS) daisy wheel follow the standard layout,
+/// every character carries its own advance width in typewriter units,
+/// 1/120 of an inch with the default resolution.
+pub static SYMBOLS: [Symbol; 127] = [
+    Symbol::new('.').petal(1).mild().width(6),
+    Symbol::new(',').petal(2).mild().width(6),
+    Symbol::new('-').petal(3).mild().width(8),
+    Symbol::new('v').petal(4).width(10),
+    Symbol::new('l').petal(5).width(6),
+    Symbol::new('m').petal(6).width(16),
+    Symbol::new('j').petal(7).width(6),
+    Symbol::new('w').petal(8).width(16),
+    Symbol::new('²').petal(9).width(8), // U+00B2
+    Symbol::new('µ').petal(10).width(10),
+    Symbol::new('f').petal(11).width(8),
+    Symbol::new('^').petal(12).width(8),
+    Symbol::new('>').petal(13).width(12),
+    Symbol::new('´').petal(14).width(6), // acute accent
+    Symbol::new('’').petal(14).width(6), // a replacement for the apostrophe
+    Symbol::new('+').petal(15).width(12),
+    Symbol::new('1').petal(16).width(12),
+    Symbol::new('2').petal(17).width(12),
+    Symbol::new('3').petal(18).width(12),
+    Symbol::new('4').petal(19).width(12),
+    Symbol::new('5').petal(20).width(12),
+    Symbol::new('6').petal(21).width(12),
+    Symbol::new('7').petal(22).width(12),
+    Symbol::new('8').petal(23).width(12),
+    Symbol::new('9').petal(24).width(12),
+    Symbol::new('0').petal(25).width(12),
+    Symbol::new('E').petal(26).width(14),
+    Symbol::new('|').petal(27).width(6),
+    Symbol::new('B').petal(28).width(14),
+    Symbol::new('F').petal(29).width(14),
+    Symbol::new('P').petal(30).width(14),
+    Symbol::new('S').petal(31).width(14),
+    Symbol::new('Z').petal(32).width(14),
+    Symbol::new('V').petal(33).width(14),
+    Symbol::new('&').petal(34).width(12),
+    Symbol::new('Y').petal(35).width(14),
+    Symbol::new('A').petal(36).width(14),
+    Symbol::new('T').petal(37).width(14),
+    Symbol::new('L').petal(38).width(14),
+    Symbol::new('$').petal(39).width(12),
+    Symbol::new('R').petal(40).width(14),
+    Symbol::new('*').petal(41).width(12),
+    Symbol::new('C').petal(42).width(14),
+    Symbol::new('"').petal(43).width(8),
+    Symbol::new('D').petal(44).width(14),
+    Symbol::new('?').petal(45).width(12),
+    Symbol::new('N').petal(46).width(14),
+    Symbol::new('I').petal(47).width(8),
+    Symbol::new('U').petal(48).width(14),
+    Symbol::new(')').petal(49).width(8),
+    Symbol::new('W').petal(50).strong().width(18),
+    Symbol::new('_').petal(51).width(12),
+    Symbol::new('=').petal(52).width(12),
+    Symbol::new(';').petal(53).width(6),
+    Symbol::new(':').petal(54).width(6),
+    Symbol::new('M').petal(55).strong().width(18),
+    Symbol::new('\'').petal(56).width(6),
+    Symbol::new('H').petal(57).width(14),
+    Symbol::new('(').petal(58).width(8),
+    Symbol::new('K').petal(59).width(14),
+    Symbol::new('/').petal(60).width(8),
+    Symbol::new('O').petal(61).strong().width(14),
+    Symbol::new('!').petal(62).width(6),
+    Symbol::new('X').petal(63).width(14),
+    Symbol::new('§').petal(64).strong().width(12),
+    Symbol::new('Q').petal(65).strong().width(14),
+    Symbol::new('J').petal(66).width(8),
+    Symbol::new('%').petal(67).width(12),
+    Symbol::new('³').petal(68).width(8), // U+00B3
+    Symbol::new('G').petal(69).width(14),
+    Symbol::new('°').petal(70).width(8),
+    Symbol::new('Ü').petal(71).strong().width(14),
+    Symbol::new('`').petal(72).mild().width(6), // grave accent
+    Symbol::new('Ö').petal(73).width(14),
+    Symbol::new('<').petal(74).width(12),
+    Symbol::new('Ä').petal(75).strong().width(14),
+    Symbol::new('#').petal(76).width(12),
+    Symbol::new('t').petal(77).width(8),
+    Symbol::new('x').petal(78).width(10),
+    Symbol::new('q').petal(79).width(10),
+    Symbol::new('ß').petal(80).width(10),
+    Symbol::new('ü').petal(81).width(10),
+    Symbol::new('ö').petal(82).width(10),
+    Symbol::new('ä').petal(83).width(10),
+    Symbol::new('y').petal(84).width(10),
+    Symbol::new('k').petal(85).width(10),
+    Symbol::new('p').petal(86).width(10),
+    Symbol::new('h').petal(87).width(10),
+    Symbol::new('c').petal(88).width(10),
+    Symbol::new('g').petal(89).width(10),
+    Symbol::new('n').petal(90).width(10),
+    Symbol::new('r').petal(91).width(8),
+    Symbol::new('s').petal(92).width(10),
+    Symbol::new('e').petal(93).width(10),
+    Symbol::new('a').petal(94).width(10),
+    Symbol::new('i').petal(95).width(6),
+    Symbol::new('d').petal(96).width(10),
+    Symbol::new('u').petal(97).width(10),
+    Symbol::new('b').petal(98).width(10),
+    Symbol::new('o').petal(99).width(10),
+    Symbol::new('z').petal(100).width(10),
+    Symbol::new('ù').petal(97).grave().width(10),
+    Symbol::new('Ù').petal(48).grave().width(14),
+    Symbol::new('è').petal(93).grave().width(10),
+    Symbol::new('È').petal(26).grave().width(14),
+    Symbol::new('ì').petal(95).grave().width(6),
+    Symbol::new('Ì').petal(47).grave().width(8),
+    Symbol::new('à').petal(94).grave().width(10),
+    Symbol::new('À').petal(36).grave().strong().width(14),
+    Symbol::new('ò').petal(99).grave().width(10),
+    Symbol::new('Ò').petal(61).grave().strong().width(14),
+    Symbol::new('ú').petal(97).acute().width(10),
+    Symbol::new('Ú').petal(48).acute().width(14),
+    Symbol::new('é').petal(93).acute().width(10),
+    Symbol::new('É').petal(26).acute().width(14),
+    Symbol::new('í').petal(95).acute().width(6),
+    Symbol::new('Í').petal(47).acute().width(8),
+    Symbol::new('á').petal(94).acute().width(10),
+    Symbol::new('Á').petal(36).acute().strong().width(14),
+    Symbol::new('ó').petal(99).acute().width(10),
+    Symbol::new('Ó').petal(61).acute().strong().width(14),
+    Symbol::whitespace().width(8),
+    Symbol::line_feed(),
+    Symbol::tab(),
+    Symbol::carriage_return(),
+    Symbol::form_feed(),
+    Symbol::backspace(),
+];
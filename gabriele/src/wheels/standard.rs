@@ -1,6 +1,6 @@
 use crate::symbol::Symbol;
 
-pub static SYMBOLS: [Symbol; 123] = [
+pub static SYMBOLS: [Symbol; 127] = [
     Symbol::new('.').petal(1).mild(),
     Symbol::new(',').petal(2).mild(),
     Symbol::new('-').petal(3).mild(),
@@ -124,4 +124,8 @@ pub static SYMBOLS: [Symbol; 123] = [
     Symbol::new('Ó').petal(61).acute().strong(),
     Symbol::whitespace(),
     Symbol::line_feed(),
+    Symbol::tab(),
+    Symbol::carriage_return(),
+    Symbol::form_feed(),
+    Symbol::backspace(),
 ];
@@ -1,109 +1,141 @@
-use bytes::Bytes;
+use crate::transport::{Stalled, Status, Transport};
+use anyhow::{bail, Context};
 use log::{debug, error, warn};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
-use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
-use tokio::sync::Notify;
-use tokio::task::JoinHandle;
-use tokio_util::sync::CancellationToken;
 
-pub fn run_tcp_client(
-    addr: SocketAddr,
-    rx: Receiver<Bytes>,
-    notifier: Arc<Notify>,
-    token: CancellationToken,
-) -> JoinHandle<()> {
-    debug!("+++TCP Client is starting");
+/// The longest time to wait for the echo of a byte,
+/// the carriage return over the full carriage travel takes a few seconds.
+pub const DEFAULT_ECHO_TIMEOUT: Duration = Duration::from_secs(10);
 
-    tokio::spawn(async move {
-        loop {
-            debug!("Subscribed to receiver");
-            tokio::select! {
-                result = TcpStream::connect(addr) => {
-                    match result {
-                        Ok(stream) => {
-                            warn!("Gabriele Connection established");
-                            notifier.notify_waiters();
-                            process_stream(stream, rx).await;
-                            warn!("TCP Connection closed");
-                            break;
-                        }
-                        Err(_e) => {
-                            error!("Error establishing TCP connection");
-                            if token.is_cancelled() {
-                                warn!("token is cancelled");
-                                break;
-                            }
-                            // wait 5s before connecting a new client
-                            tokio::time::sleep(tokio::time::Duration::from_millis(5000)).await;
-                        }
-                    }
-                }
+/// The pause between the attempts to connect to the bridge.
+pub const CONNECT_INTERVAL: Duration = Duration::from_secs(5);
 
-                _ = token.cancelled() => {
-                   warn!("Cancelled loop");
-                    break;
-                }
+/// Talks to the typewriter through the RP2040 bridge,
+/// the bridge confirms every byte by sending it back.
+pub struct TcpTransport {
+    addr: SocketAddr,
+    stream: Option<TcpStream>,
+    timeout: Duration,
+    retries: u32,
+    connect_attempts: Option<u32>,
+    stalled: bool,
+}
 
-                _ = tokio::signal::ctrl_c() => {
-                    warn!("Ctrl-C is captured for the tcp client");
-                    break;
-                }
-            }
+impl TcpTransport {
+    pub fn new(addr: SocketAddr) -> Self {
+        TcpTransport {
+            addr,
+            stream: None,
+            timeout: DEFAULT_ECHO_TIMEOUT,
+            retries: 0,
+            connect_attempts: None,
+            stalled: false,
         }
-    })
-}
+    }
 
-async fn process_stream(stream: TcpStream, mut receiver: Receiver<Bytes>) {
-    let (mut rx, mut tx) = stream.into_split();
+    /// The time to wait for the echo of every byte.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
 
-    'outer: loop {
-        tokio::select! {
-            result = receiver.recv() => {
-                match result {
-                    Ok(chunk) => {
-                        debug!("Client received bytes {:02x}", chunk);
-                        // this is regarded as inner loop
-                        for byte in chunk {
-                            if let Err(e) = tx.write_u8(byte).await {
-                               error!("Socket write error {e:?}");
-                               break 'outer;
-                            } else {
-                                match rx.read_u8().await {
-                                    Ok(reply) if (reply != byte) => {
-                                        error!("Expected reply is {:02x} but received {:02x}", byte, reply);
-                                        break 'outer;
-                                    }
-                                    Err(e) => {
-                                        error!("Socket read error {e:?}");
-                                        break 'outer;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                    },
+    /// How many times more to wait for a missing echo before giving up.
+    /// The byte is not sent again, it may have been printed already.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
 
-                    Err(RecvError::Lagged(n)) => {
-                        error!("Receiver is lagged {n} messages");
-                        break
-                    }
+    /// How many times to try connecting to the bridge, at least once,
+    /// without the limit the attempts go on until the bridge is reached.
+    pub fn with_connect_attempts(mut self, attempts: u32) -> Self {
+        self.connect_attempts = Some(attempts.max(1));
+        self
+    }
+}
 
-                    Err(RecvError::Closed) => {
-                        warn!("Channel is closed");
-                        break
+impl Transport for TcpTransport {
+    /// Tries to connect every `CONNECT_INTERVAL` until the bridge accepts the connection,
+    /// fails when it has not been accepted within the number of attempts, if it is limited.
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        debug!("+++TCP Client is starting");
+        let mut attempts = 0;
+        loop {
+            match TcpStream::connect(self.addr).await {
+                Ok(stream) => {
+                    warn!("Gabriele Connection established");
+                    self.stream = Some(stream);
+                    self.stalled = false;
+                    return Ok(());
+                }
+                Err(e) => {
+                    attempts += 1;
+                    match self.connect_attempts {
+                        Some(limit) => {
+                            error!("Error establishing TCP connection {attempts}/{limit}")
+                        }
+                        None => error!("Error establishing TCP connection {attempts}"),
                     }
-
+                    if self.connect_attempts.is_some_and(|limit| attempts >= limit) {
+                        return Err(e).with_context(|| {
+                            format!("cannot connect to {} after {attempts} attempts", self.addr)
+                        });
+                    }
+                    tokio::time::sleep(CONNECT_INTERVAL).await;
                 }
             }
+        }
+    }
 
-            _ = tokio::signal::ctrl_c() => {
-                warn!("Ctrl-C received, exit reader");
-                break;
+    async fn send(&mut self, byte: u8) -> anyhow::Result<()> {
+        let Some(stream) = self.stream.as_mut() else {
+            bail!("cannot transmit bytes, TCP connection is closed");
+        };
+        debug!("Client sends byte {byte:02x}");
+        stream.write_u8(byte).await.context("Socket write error")?;
+        let mut attempts = 0;
+        let reply = loop {
+            match tokio::time::timeout(self.timeout, stream.read_u8()).await {
+                Ok(reply) => break reply.context("Socket read error")?,
+                Err(_) if attempts < self.retries => {
+                    attempts += 1;
+                    warn!(
+                        "No reply for {byte:02x} within {:?}, waiting again {attempts}/{}",
+                        self.timeout, self.retries
+                    );
+                }
+                Err(_) => {
+                    self.stalled = true;
+                    let waited = self.timeout * (attempts + 1);
+                    return Err(Stalled { byte, waited }.into());
+                }
             }
+        };
+        if reply != byte {
+            bail!("Expected reply is {byte:02x} but received {reply:02x}");
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            stream
+                .shutdown()
+                .await
+                .context("cannot close TCP connection")?;
+            warn!("TCP Connection closed");
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> Status {
+        match (&self.stream, self.stalled) {
+            (_, true) => Status::Stalled,
+            (Some(_), false) => Status::Connected,
+            (None, false) => Status::Disconnected,
         }
-    } // end loop
+    }
 }
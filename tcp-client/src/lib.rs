@@ -1,3 +1,5 @@
 mod client;
+mod transport;
 
-pub use client::run_tcp_client;
+pub use client::{TcpTransport, CONNECT_INTERVAL, DEFAULT_ECHO_TIMEOUT};
+pub use transport::{Stalled, Status, Transport};
@@ -0,0 +1,52 @@
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+/// Whether the typewriter can be reached through the `Transport`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Status {
+    #[default]
+    Disconnected,
+    Connected,
+    /// The typewriter has stopped confirming the bytes,
+    /// for example it is jammed or the bridge has dropped off the network
+    Stalled,
+}
+
+/// The typewriter has not confirmed the `byte` within the time it was `waited` for.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Stalled {
+    pub byte: u8,
+    pub waited: Duration,
+}
+
+impl fmt::Display for Stalled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the typewriter has not confirmed {:02x} within {:?}",
+            self.byte, self.waited
+        )
+    }
+}
+
+impl std::error::Error for Stalled {}
+
+/// The link between the host and the typewriter, every byte sent
+/// through it is confirmed by the typewriter before the next one goes out.
+///
+/// The futures are `Send` to let the `Hal` run on its own task,
+/// the implementations are written with plain `async fn`.
+pub trait Transport {
+    /// Opens the link, returns once the typewriter can be reached.
+    fn connect(&mut self) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Sends a single byte and waits until the typewriter confirms it,
+    /// fails with `Stalled` when the confirmation does not come in time.
+    fn send(&mut self, byte: u8) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Closes the link, nothing can be sent until it is connected again.
+    fn disconnect(&mut self) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    fn status(&self) -> Status;
+}